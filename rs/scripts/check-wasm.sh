@@ -0,0 +1,17 @@
+#!/bin/sh
+# Build every cdylib crate for wasm32-unknown-unknown with the wasm feature,
+# as wasm-pack does. This catches wasm-bindgen export names that collide,
+# which a native build does not see.
+#
+# Needs `rustup target add wasm32-unknown-unknown`. Run from rs/:
+#   scripts/check-wasm.sh
+
+set -e
+
+for manifest in */Cargo.toml; do
+  if grep -q '"cdylib"' "$manifest"; then
+    crate=$(dirname "$manifest")
+    echo "Checking $crate"
+    cargo build -p "$crate" --features wasm --target wasm32-unknown-unknown --release
+  fi
+done
@@ -0,0 +1,20 @@
+#!/usr/bin/env -S node --no-warnings
+// Cargo runner for wasm32-wasip1: runs a test binary under Node's WASI, with
+// the package directory preopened so tests can read their vector files.
+//
+// Configured in .cargo/config.toml, so from rs/:
+//   cargo test -p webbuf --target wasm32-wasip1
+
+import { readFile } from "node:fs/promises";
+import { WASI } from "node:wasi";
+
+const [, , file, ...args] = process.argv;
+const wasi = new WASI({
+  version: "preview1",
+  args: [file, ...args],
+  env: process.env,
+  preopens: { ".": "." },
+});
+const module = await WebAssembly.compile(await readFile(file));
+const instance = await WebAssembly.instantiate(module, wasi.getImportObject());
+process.exitCode = wasi.start(instance);
@@ -0,0 +1,58 @@
+// Native hex and base64 throughput
+//
+// criterion does not build for wasm32, so this only measures the scalar path.
+// benches/simd.rs compares the scalar and simd128 code on wasm32.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use criterion::{black_box, criterion_group, BenchmarkId, Criterion, Throughput};
+    use webbuf::{decode_base64, decode_hex, encode_base64, encode_hex};
+
+    const SIZES: [usize; 4] = [32, 1024, 64 * 1024, 1024 * 1024];
+
+    fn test_data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 + 7) as u8).collect()
+    }
+
+    fn bench_hex(c: &mut Criterion) {
+        let mut group = c.benchmark_group("hex");
+        for size in SIZES {
+            let data = test_data(size);
+            let encoded = encode_hex(&data);
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(BenchmarkId::new("encode", size), &data, |b, data| {
+                b.iter(|| encode_hex(black_box(data)))
+            });
+            group.bench_with_input(BenchmarkId::new("decode", size), &encoded, |b, encoded| {
+                b.iter(|| decode_hex(black_box(encoded)).unwrap())
+            });
+        }
+        group.finish();
+    }
+
+    fn bench_base64(c: &mut Criterion) {
+        let mut group = c.benchmark_group("base64");
+        for size in SIZES {
+            let data = test_data(size);
+            let encoded = encode_base64(&data);
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(BenchmarkId::new("encode", size), &data, |b, data| {
+                b.iter(|| encode_base64(black_box(data)))
+            });
+            group.bench_with_input(BenchmarkId::new("decode", size), &encoded, |b, encoded| {
+                b.iter(|| decode_base64(black_box(encoded)).unwrap())
+            });
+        }
+        group.finish();
+    }
+
+    criterion_group!(benches, bench_hex, bench_base64);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(native::benches);
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    println!("The codecs bench is native-only. Use the simd bench on wasm32.");
+}
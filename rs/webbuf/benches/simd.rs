@@ -0,0 +1,89 @@
+// Scalar against simd128 hex and base64 throughput
+//
+// A plain timing loop, since criterion does not build for wasm32. The scalar
+// column calls the `hex` and `base64` crates directly, which is what webbuf
+// uses without simd128, and the webbuf column calls the public functions.
+// With the wasm32-wasip1 runner in rs/.cargo/config.toml, from rs/:
+//
+//   cargo bench -p webbuf --bench simd --target wasm32-wasip1
+//
+// Natively both columns measure the scalar code.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use webbuf::{decode_base64, decode_hex, encode_base64, encode_hex};
+
+const SIZES: [usize; 3] = [1024, 64 * 1024, 1024 * 1024];
+
+// How long each measurement runs for
+const MEASURE_TIME: Duration = Duration::from_millis(300);
+
+fn test_data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 + 7) as u8).collect()
+}
+
+// Throughput in MB/s of unencoded data
+fn throughput(len: usize, mut f: impl FnMut()) -> f64 {
+    f();
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while start.elapsed() < MEASURE_TIME {
+        f();
+        iterations += 1;
+    }
+    (len as u64 * iterations) as f64 / start.elapsed().as_secs_f64() / 1e6
+}
+
+fn report(name: &str, size: usize, scalar: f64, webbuf: f64) {
+    println!(
+        "{:<14} {:>8} {:>10.0} MB/s {:>10.0} MB/s {:>7.2}x",
+        name,
+        size,
+        scalar,
+        webbuf,
+        webbuf / scalar
+    );
+}
+
+fn main() {
+    let simd = cfg!(all(target_arch = "wasm32", target_feature = "simd128"));
+    println!("webbuf path: {}", if simd { "simd128" } else { "scalar" });
+    println!(
+        "{:<14} {:>8} {:>15} {:>15} {:>8}",
+        "codec", "bytes", "scalar", "webbuf", "speedup"
+    );
+
+    for size in SIZES {
+        let data = test_data(size);
+        let hex = encode_hex(&data);
+        let base64 = encode_base64(&data);
+
+        report(
+            "hex encode",
+            size,
+            throughput(size, || drop(black_box(hex::encode(black_box(&data))))),
+            throughput(size, || drop(black_box(encode_hex(black_box(&data))))),
+        );
+        report(
+            "hex decode",
+            size,
+            throughput(size, || drop(black_box(hex::decode(black_box(&hex))))),
+            throughput(size, || drop(black_box(decode_hex(black_box(&hex))))),
+        );
+        report(
+            "base64 encode",
+            size,
+            throughput(size, || drop(black_box(STANDARD.encode(black_box(&data))))),
+            throughput(size, || drop(black_box(encode_base64(black_box(&data))))),
+        );
+        report(
+            "base64 decode",
+            size,
+            throughput(size, || {
+                drop(black_box(STANDARD.decode(black_box(&base64))))
+            }),
+            throughput(size, || drop(black_box(decode_base64(black_box(&base64))))),
+        );
+    }
+}
@@ -0,0 +1,180 @@
+// Base45 (RFC 9285), the encoding used for data in QR alphanumeric mode
+//
+// Each pair of bytes becomes three characters, least significant digit first,
+// and a trailing single byte becomes two.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const BASE45_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+fn base45_digit(symbol: u8) -> Option<u32> {
+    BASE45_ALPHABET
+        .iter()
+        .position(|&c| c == symbol)
+        .map(|d| d as u32)
+}
+
+/// Number of characters `encode_base45` produces for `len` bytes
+/// QR codes store Base45 text in alphanumeric mode, so compare this with
+/// the alphanumeric capacity of the QR version and error correction level.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encoded_len_base45(len: usize) -> Result<usize, String> {
+    (len / 2)
+        .checked_mul(3)
+        .and_then(|n| n.checked_add(len % 2 * 2))
+        .ok_or_else(|| "input too large".to_string())
+}
+
+/// Encode a byte slice into a Base45 string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_base45(data: &[u8]) -> String {
+    let mut out = Vec::with_capacity(data.len().div_ceil(2) * 3);
+    for chunk in data.chunks(2) {
+        let (mut value, digits) = match *chunk {
+            [a, b] => ((a as usize) << 8 | b as usize, 3),
+            [a] => (a as usize, 2),
+            _ => unreachable!("chunks(2) yields one or two bytes"),
+        };
+        for _ in 0..digits {
+            out.push(BASE45_ALPHABET[value % 45]);
+            value /= 45;
+        }
+    }
+    String::from_utf8(out).expect("base45 alphabet is ASCII")
+}
+
+/// Decode a Base45 string into a byte vector
+/// Only the 45 alphabet characters are accepted, and every triplet must
+/// encode a value of at most 65535 (a final pair at most 255), so each byte
+/// string has exactly one encoding.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base45(encoded: &str) -> Result<Vec<u8>, String> {
+    let bytes = encoded.as_bytes();
+    if bytes.len() % 3 == 1 {
+        return Err("invalid base45: invalid length".to_string());
+    }
+    let mut out = Vec::with_capacity(bytes.len() / 3 * 2 + 1);
+    for (i, group) in bytes.chunks(3).enumerate() {
+        let offset = i * 3;
+        let mut value = 0;
+        let mut weight = 1;
+        for (j, &symbol) in group.iter().enumerate() {
+            // Everything before the first invalid byte is ASCII, so this
+            // offset is on a character boundary
+            let digit = base45_digit(symbol).ok_or_else(|| {
+                format!(
+                    "invalid base45: invalid character '{}' at offset {}",
+                    encoded[offset + j..].chars().next().unwrap_or('?'),
+                    offset + j
+                )
+            })?;
+            value += digit * weight;
+            weight *= 45;
+        }
+        if group.len() == 3 {
+            if value > 0xffff {
+                return Err(format!(
+                    "invalid base45: triplet value out of range at offset {}",
+                    offset
+                ));
+            }
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        } else {
+            if value > 0xff {
+                return Err(format!(
+                    "invalid base45: final pair value out of range at offset {}",
+                    offset
+                ));
+            }
+            out.push(value as u8);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base45_rfc_vectors() {
+        // RFC 9285 section 4.3 and 4.4
+        let vectors: [(&[u8], &str); 4] = [
+            (b"AB", "BB8"),
+            (b"Hello!!", "%69 VD92EX0"),
+            (b"base-45", "UJCLQE7W581"),
+            (b"ietf!", "QED8WEX0"),
+        ];
+        for (data, encoded) in vectors {
+            assert_eq!(encode_base45(data), encoded);
+            assert_eq!(decode_base45(encoded).unwrap(), data);
+        }
+        assert_eq!(encode_base45(b""), "");
+        assert_eq!(decode_base45("").unwrap(), b"");
+    }
+
+    #[test]
+    fn test_base45_roundtrip_edges() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        for len in [1, 2, 3, 255, 256] {
+            let encoded = encode_base45(&data[..len]);
+            assert_eq!(encoded.len(), encoded_len_base45(len).unwrap());
+            assert_eq!(decode_base45(&encoded).unwrap(), &data[..len]);
+        }
+        assert_eq!(encode_base45(&[0xff, 0xff]), "FGW");
+        assert_eq!(encode_base45(&[0xff]), "U5");
+    }
+
+    #[test]
+    fn test_decode_base45_rejects_out_of_range_values() {
+        // "GGW" is 65536, one past the largest 16-bit value
+        assert_eq!(
+            decode_base45("GGW").unwrap_err(),
+            "invalid base45: triplet value out of range at offset 0"
+        );
+        assert_eq!(
+            decode_base45("BB8:::").unwrap_err(),
+            "invalid base45: triplet value out of range at offset 3"
+        );
+        // "V5" is 256
+        assert_eq!(
+            decode_base45("BB8V5").unwrap_err(),
+            "invalid base45: final pair value out of range at offset 3"
+        );
+    }
+
+    #[test]
+    fn test_decode_base45_rejects_bad_input() {
+        assert_eq!(
+            decode_base45("BB8A").unwrap_err(),
+            "invalid base45: invalid length"
+        );
+        assert_eq!(
+            decode_base45("bb8").unwrap_err(),
+            "invalid base45: invalid character 'b' at offset 0"
+        );
+        assert_eq!(
+            decode_base45("BB8B=").unwrap_err(),
+            "invalid base45: invalid character '=' at offset 4"
+        );
+    }
+
+    #[test]
+    fn test_decode_base45_reports_non_ascii_character() {
+        assert_eq!(
+            decode_base45("BB8\u{e9}A").unwrap_err(),
+            "invalid base45: invalid character '\u{e9}' at offset 3"
+        );
+    }
+
+    #[test]
+    fn test_encoded_len_base45() {
+        assert_eq!(encoded_len_base45(0).unwrap(), 0);
+        assert_eq!(encoded_len_base45(1).unwrap(), 2);
+        assert_eq!(encoded_len_base45(2).unwrap(), 3);
+        // Ed25519 signature plus public key
+        assert_eq!(encoded_len_base45(64 + 32).unwrap(), 144);
+        assert!(encoded_len_base45(usize::MAX).is_err());
+    }
+}
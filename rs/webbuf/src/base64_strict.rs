@@ -0,0 +1,332 @@
+// Strict (canonical) and MIME base64 decoding with structured errors
+
+use base64::{engine::general_purpose as lib_base64, DecodeError as Base64DecodeError, Engine};
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// MIME (RFC 2045) limits encoded lines to 76 characters
+const MIME_LINE_LENGTH: usize = 76;
+
+/// What went wrong while decoding base64
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base64ErrorKind {
+    /// A byte outside the standard alphabet
+    InvalidCharacter,
+    /// The number of symbols cannot come from any input length
+    InvalidLength,
+    /// Padding is missing, too long, or not at the end
+    InvalidPadding,
+    /// The last symbol has non-zero bits that do not encode any data
+    NonCanonicalTrailingBits,
+    /// A line break that is not at a 76-column boundary, or a line over 76
+    /// characters (MIME mode only)
+    InvalidLineBreak,
+}
+
+impl Base64ErrorKind {
+    fn description(self) -> &'static str {
+        match self {
+            Base64ErrorKind::InvalidCharacter => "invalid character",
+            Base64ErrorKind::InvalidLength => "invalid length",
+            Base64ErrorKind::InvalidPadding => "invalid padding",
+            Base64ErrorKind::NonCanonicalTrailingBits => "non-canonical trailing bits",
+            Base64ErrorKind::InvalidLineBreak => "line break not at a 76-column boundary",
+        }
+    }
+}
+
+/// A base64 decoding error with the byte offset in the original input
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Base64Error {
+    kind: Base64ErrorKind,
+    offset: usize,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Base64Error {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn kind(&self) -> Base64ErrorKind {
+        self.kind
+    }
+
+    /// Byte offset into the input where the error was detected
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Human-readable description, e.g. "invalid base64: invalid padding at offset 2"
+    pub fn message(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid base64: {} at offset {}",
+            self.kind.description(),
+            self.offset
+        )
+    }
+}
+
+impl std::error::Error for Base64Error {}
+
+impl From<Base64Error> for String {
+    fn from(err: Base64Error) -> String {
+        err.to_string()
+    }
+}
+
+/// Translate a decode error on `symbols` into a `Base64Error`
+fn to_base64_error(err: Base64DecodeError, symbols: &[u8]) -> Base64Error {
+    let (kind, offset) = match err {
+        Base64DecodeError::InvalidByte(offset, b'=') => (Base64ErrorKind::InvalidPadding, offset),
+        Base64DecodeError::InvalidByte(offset, _) => (Base64ErrorKind::InvalidCharacter, offset),
+        Base64DecodeError::InvalidLength(_) => (Base64ErrorKind::InvalidLength, symbols.len()),
+        Base64DecodeError::InvalidLastSymbol(offset, _) => {
+            (Base64ErrorKind::NonCanonicalTrailingBits, offset)
+        }
+        Base64DecodeError::InvalidPadding => {
+            // Point at the first padding byte, or at the end if it is missing
+            let offset = symbols
+                .iter()
+                .position(|&c| c == b'=')
+                .unwrap_or(symbols.len());
+            (Base64ErrorKind::InvalidPadding, offset)
+        }
+    };
+    Base64Error { kind, offset }
+}
+
+/// Decode a base64 string, accepting only its canonical encoding
+/// Rejects whitespace, missing or extra padding, and non-zero trailing bits,
+/// so that exactly one string decodes to any given byte sequence.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base64_strict(encoded: &str) -> Result<Vec<u8>, Base64Error> {
+    lib_base64::STANDARD
+        .decode(encoded)
+        .map_err(|err| to_base64_error(err, encoded.as_bytes()))
+}
+
+/// Decode MIME base64 with lines of exactly 76 characters
+/// Line breaks (CRLF or LF) are only skipped at 76-column boundaries, plus a
+/// single optional line break at the very end. The decoded data must still
+/// be canonical, and error offsets refer to the original input.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base64_mime(encoded: &str) -> Result<Vec<u8>, Base64Error> {
+    let bytes = encoded.as_bytes();
+    let mut symbols = Vec::with_capacity(bytes.len());
+    // Original offset of the first symbol of each line
+    let mut line_starts = vec![0];
+    let mut column = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let break_len = match bytes[i] {
+            b'\n' => 1,
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => 2,
+            _ => 0,
+        };
+        if break_len == 0 {
+            if column == MIME_LINE_LENGTH {
+                return Err(Base64Error {
+                    kind: Base64ErrorKind::InvalidLineBreak,
+                    offset: i,
+                });
+            }
+            symbols.push(bytes[i]);
+            column += 1;
+            i += 1;
+            continue;
+        }
+        let at_end = i + break_len == bytes.len();
+        if column != MIME_LINE_LENGTH && !(at_end && column > 0) {
+            return Err(Base64Error {
+                kind: Base64ErrorKind::InvalidLineBreak,
+                offset: i,
+            });
+        }
+        i += break_len;
+        column = 0;
+        line_starts.push(i);
+    }
+
+    lib_base64::STANDARD.decode(&symbols).map_err(|err| {
+        let mut err = to_base64_error(err, &symbols);
+        let line = err.offset / MIME_LINE_LENGTH;
+        if let Some(start) = line_starts.get(line) {
+            err.offset = start + err.offset % MIME_LINE_LENGTH;
+        } else {
+            err.offset = bytes.len();
+        }
+        err
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_base64;
+
+    fn err(kind: Base64ErrorKind, offset: usize) -> Base64Error {
+        Base64Error { kind, offset }
+    }
+
+    /// Wrap base64 text at 76 columns with the given line ending
+    fn wrap(text: &str, line_ending: &str) -> String {
+        text.as_bytes()
+            .chunks(MIME_LINE_LENGTH)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect::<Vec<_>>()
+            .join(line_ending)
+    }
+
+    #[test]
+    fn test_decode_base64_strict_valid() {
+        assert_eq!(decode_base64_strict("").unwrap(), b"");
+        assert_eq!(decode_base64_strict("Zg==").unwrap(), b"f");
+        assert_eq!(
+            decode_base64_strict("SGVsbG8sIHdvcmxkIQ==").unwrap(),
+            b"Hello, world!"
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_strict_invalid_character() {
+        assert_eq!(
+            decode_base64_strict("SGVs-G8=").unwrap_err(),
+            err(Base64ErrorKind::InvalidCharacter, 4)
+        );
+        assert_eq!(
+            decode_base64_strict("SGVs bG8=").unwrap_err(),
+            err(Base64ErrorKind::InvalidCharacter, 4)
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_strict_non_canonical_trailing_bits() {
+        // "Zh==" and "Zm9=" carry bits that canonical encoders leave as zero
+        assert_eq!(
+            decode_base64_strict("Zh==").unwrap_err(),
+            err(Base64ErrorKind::NonCanonicalTrailingBits, 1)
+        );
+        assert_eq!(
+            decode_base64_strict("Zm9=").unwrap_err(),
+            err(Base64ErrorKind::NonCanonicalTrailingBits, 2)
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_strict_bad_padding() {
+        assert_eq!(
+            decode_base64_strict("Zg").unwrap_err(),
+            err(Base64ErrorKind::InvalidPadding, 2)
+        );
+        assert_eq!(
+            decode_base64_strict("Zg=").unwrap_err(),
+            err(Base64ErrorKind::InvalidPadding, 2)
+        );
+        assert_eq!(
+            decode_base64_strict("Zm8==").unwrap_err().kind(),
+            Base64ErrorKind::InvalidPadding
+        );
+        assert_eq!(
+            decode_base64_strict("Zg==Zg==").unwrap_err(),
+            err(Base64ErrorKind::InvalidPadding, 2)
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_strict_invalid_length() {
+        assert_eq!(
+            decode_base64_strict("Z").unwrap_err(),
+            err(Base64ErrorKind::InvalidLength, 1)
+        );
+        assert_eq!(
+            decode_base64_strict("Zm9vY").unwrap_err(),
+            err(Base64ErrorKind::InvalidLength, 5)
+        );
+    }
+
+    #[test]
+    fn test_base64_error_message() {
+        let error = decode_base64_strict("Zg").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "invalid base64: invalid padding at offset 2"
+        );
+        assert_eq!(String::from(error), error.message());
+    }
+
+    #[test]
+    fn test_decode_base64_mime_valid() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let encoded = encode_base64(&data);
+        for line_ending in ["\r\n", "\n"] {
+            let wrapped = wrap(&encoded, line_ending);
+            assert_eq!(decode_base64_mime(&wrapped).unwrap(), data);
+            let trailing = format!("{}{}", wrapped, line_ending);
+            assert_eq!(decode_base64_mime(&trailing).unwrap(), data);
+        }
+        assert_eq!(decode_base64_mime(&encoded[..76]).unwrap(), data[..57]);
+    }
+
+    #[test]
+    fn test_decode_base64_mime_rejects_misplaced_line_break() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let encoded = encode_base64(&data);
+        let broken = format!("{}\r\n{}", &encoded[..40], &encoded[40..]);
+        assert_eq!(
+            decode_base64_mime(&broken).unwrap_err(),
+            err(Base64ErrorKind::InvalidLineBreak, 40)
+        );
+        assert_eq!(
+            decode_base64_mime("\r\nZg==").unwrap_err(),
+            err(Base64ErrorKind::InvalidLineBreak, 0)
+        );
+        assert_eq!(
+            decode_base64_mime("Zg==\r\n\r\n").unwrap_err(),
+            err(Base64ErrorKind::InvalidLineBreak, 4)
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_mime_rejects_long_line() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let encoded = encode_base64(&data);
+        assert_eq!(
+            decode_base64_mime(&encoded).unwrap_err(),
+            err(Base64ErrorKind::InvalidLineBreak, 76)
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_mime_rejects_other_whitespace() {
+        assert_eq!(
+            decode_base64_mime("Zm9v YmFy").unwrap_err(),
+            err(Base64ErrorKind::InvalidCharacter, 4)
+        );
+        assert_eq!(
+            decode_base64_mime("Zg==\r").unwrap_err(),
+            err(Base64ErrorKind::InvalidCharacter, 4)
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_mime_error_offset_in_original_input() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let mut wrapped = wrap(&encode_base64(&data), "\r\n").into_bytes();
+        // Second line starts at 78; corrupt its sixth character
+        wrapped[83] = b'*';
+        let wrapped = String::from_utf8(wrapped).unwrap();
+        assert_eq!(
+            decode_base64_mime(&wrapped).unwrap_err(),
+            err(Base64ErrorKind::InvalidCharacter, 83)
+        );
+    }
+}
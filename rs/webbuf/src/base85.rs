@@ -0,0 +1,320 @@
+// Base85 family: Ascii85 (Adobe/btoa), Z85 (ZeroMQ) and git/Python base85
+//
+// All three map each 4-byte big-endian group to 5 base-85 digits and differ
+// only in the alphabet and in how they treat zero groups, whitespace and
+// partial final groups.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+const RFC1924_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+const ASCII85_FIRST: u8 = b'!';
+
+struct Base85Format {
+    name: &'static str,
+    /// Encoding alphabet, or `None` for Ascii85's contiguous `!`..`u` range
+    alphabet: Option<&'static [u8; 85]>,
+    /// Whether an all-zero group is written as a single `z`
+    zero_shortcut: bool,
+}
+
+const ASCII85: Base85Format = Base85Format {
+    name: "ascii85",
+    alphabet: None,
+    zero_shortcut: true,
+};
+const Z85: Base85Format = Base85Format {
+    name: "z85",
+    alphabet: Some(Z85_ALPHABET),
+    zero_shortcut: false,
+};
+const GIT_BASE85: Base85Format = Base85Format {
+    name: "base85",
+    alphabet: Some(RFC1924_ALPHABET),
+    zero_shortcut: false,
+};
+
+impl Base85Format {
+    fn symbol(&self, digit: u32) -> u8 {
+        match self.alphabet {
+            Some(alphabet) => alphabet[digit as usize],
+            None => ASCII85_FIRST + digit as u8,
+        }
+    }
+
+    fn digit(&self, symbol: u8) -> Option<u32> {
+        match self.alphabet {
+            Some(alphabet) => alphabet.iter().position(|&c| c == symbol).map(|d| d as u32),
+            None => symbol
+                .checked_sub(ASCII85_FIRST)
+                .filter(|&d| d < 85)
+                .map(|d| d as u32),
+        }
+    }
+
+    /// Encode 4-byte groups, writing a partial final group of n bytes as n + 1 digits
+    fn encode(&self, data: &[u8]) -> String {
+        let mut out = String::with_capacity(data.len().div_ceil(4) * 5);
+        for chunk in data.chunks(4) {
+            if self.zero_shortcut && chunk == [0, 0, 0, 0] {
+                out.push('z');
+                continue;
+            }
+            let mut group = [0u8; 4];
+            group[..chunk.len()].copy_from_slice(chunk);
+            let mut value = u32::from_be_bytes(group);
+            let mut digits = [0u8; 5];
+            for digit in digits.iter_mut().rev() {
+                *digit = self.symbol(value % 85);
+                value /= 85;
+            }
+            out.extend(digits[..chunk.len() + 1].iter().map(|&c| c as char));
+        }
+        out
+    }
+
+    /// Decode `(offset, symbol)` pairs, where offsets refer to the original input
+    fn decode(&self, symbols: impl Iterator<Item = (usize, u8)>) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        let mut group = [0u32; 5];
+        let mut group_len = 0;
+        let mut group_start = 0;
+        for (offset, symbol) in symbols {
+            if self.zero_shortcut && symbol == b'z' {
+                if group_len != 0 {
+                    return Err(format!(
+                        "invalid {}: 'z' inside a group at offset {}",
+                        self.name, offset
+                    ));
+                }
+                out.extend_from_slice(&[0; 4]);
+                continue;
+            }
+            let digit = self.digit(symbol).ok_or_else(|| {
+                format!(
+                    "invalid {}: invalid character at offset {}",
+                    self.name, offset
+                )
+            })?;
+            if group_len == 0 {
+                group_start = offset;
+            }
+            group[group_len] = digit;
+            group_len += 1;
+            if group_len == 5 {
+                out.extend_from_slice(&self.group_value(&group, group_start)?);
+                group_len = 0;
+            }
+        }
+        match group_len {
+            0 => {}
+            1 => {
+                return Err(format!(
+                    "invalid {}: final group of 1 character at offset {}",
+                    self.name, group_start
+                ));
+            }
+            _ => {
+                // Pad with the highest digit so truncation rounds back down
+                group[group_len..].fill(84);
+                let bytes = self.group_value(&group, group_start)?;
+                out.extend_from_slice(&bytes[..group_len - 1]);
+            }
+        }
+        Ok(out)
+    }
+
+    fn group_value(&self, group: &[u32; 5], offset: usize) -> Result<[u8; 4], String> {
+        let value = group.iter().fold(0u64, |acc, &d| acc * 85 + d as u64);
+        u32::try_from(value)
+            .map(u32::to_be_bytes)
+            .map_err(|_| format!("invalid {}: group overflow at offset {}", self.name, offset))
+    }
+}
+
+/// Encode a byte slice into Ascii85 without delimiters, using `z` for zero groups
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_ascii85(data: &[u8]) -> String {
+    ASCII85.encode(data)
+}
+
+/// Encode a byte slice into Adobe Ascii85, wrapped in `<~` and `~>`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_ascii85_adobe(data: &[u8]) -> String {
+    format!("<~{}~>", ASCII85.encode(data))
+}
+
+/// Decode an Ascii85 string into a byte vector
+/// Accepts the Adobe `<~`/`~>` delimiters (or just the `~>` terminator) and
+/// ignores whitespace, as both the Adobe and btoa formats allow.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_ascii85(encoded: &str) -> Result<Vec<u8>, String> {
+    let bytes = encoded.as_bytes();
+    let start = bytes.len() - encoded.trim_start().len();
+    let end = encoded.trim_end().len();
+    let mut body = start..end.max(start);
+    if bytes[body.clone()].starts_with(b"<~") {
+        if !bytes[body.clone()].ends_with(b"~>") || body.len() < 4 {
+            return Err("invalid ascii85: missing '~>' terminator".to_string());
+        }
+        body = body.start + 2..body.end - 2;
+    } else if bytes[body.clone()].ends_with(b"~>") {
+        body = body.start..body.end - 2;
+    }
+    let symbols = bytes[body.clone()]
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| (body.start + i, c))
+        .filter(|(_, c)| !c.is_ascii_whitespace());
+    ASCII85.decode(symbols)
+}
+
+/// Encode a byte slice into Z85 (ZeroMQ RFC 32)
+/// The input length must be a multiple of 4.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_z85(data: &[u8]) -> Result<String, String> {
+    if !data.len().is_multiple_of(4) {
+        return Err("invalid z85: input length must be a multiple of 4".to_string());
+    }
+    Ok(Z85.encode(data))
+}
+
+/// Decode a Z85 string into a byte vector
+/// The input length must be a multiple of 5.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_z85(encoded: &str) -> Result<Vec<u8>, String> {
+    if !encoded.len().is_multiple_of(5) {
+        return Err("invalid z85: input length must be a multiple of 5".to_string());
+    }
+    Z85.decode(encoded.bytes().enumerate())
+}
+
+/// Encode a byte slice into git/Python base85
+/// Matches git binary patches and Python's `b85encode`. The alphabet is RFC
+/// 1924's, but the input is encoded in 4-byte groups rather than as one
+/// 128-bit integer, so this is not RFC 1924 IPv6 address output. Inputs of
+/// any length are accepted.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_base85_git(data: &[u8]) -> String {
+    GIT_BASE85.encode(data)
+}
+
+/// Decode git/Python base85 into a byte vector
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base85_git(encoded: &str) -> Result<Vec<u8>, String> {
+    GIT_BASE85.decode(encoded.bytes().enumerate())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii85_vectors() {
+        // Expected values from Python's base64.a85encode
+        assert_eq!(encode_ascii85(b""), "");
+        assert_eq!(
+            encode_ascii85_adobe(b"Hello, world!"),
+            "<~87cURD_*#TDfTZ)+T~>"
+        );
+        assert_eq!(encode_ascii85(b"\0\0\0\0abc"), "z@:E^");
+        assert_eq!(encode_ascii85(b"\0\0\0"), "!!!!");
+        assert_eq!(
+            decode_ascii85("<~87cURD_*#TDfTZ)+T~>").unwrap(),
+            b"Hello, world!"
+        );
+        assert_eq!(decode_ascii85("z@:E^").unwrap(), b"\0\0\0\0abc");
+    }
+
+    #[test]
+    fn test_ascii85_delimiters_and_whitespace() {
+        let expected = b"Hello, world!";
+        assert_eq!(decode_ascii85("87cURD_*#TDfTZ)+T").unwrap(), expected);
+        assert_eq!(decode_ascii85("87cURD_*#TDfTZ)+T~>").unwrap(), expected);
+        assert_eq!(
+            decode_ascii85("  <~87cUR D_*#T\nDfTZ)\r\n+T~>\n").unwrap(),
+            expected
+        );
+        assert_eq!(decode_ascii85("<~~>").unwrap(), b"");
+    }
+
+    #[test]
+    fn test_ascii85_errors() {
+        assert_eq!(
+            decode_ascii85("<~87cUR").unwrap_err(),
+            "invalid ascii85: missing '~>' terminator"
+        );
+        assert_eq!(
+            decode_ascii85("87czUR").unwrap_err(),
+            "invalid ascii85: 'z' inside a group at offset 3"
+        );
+        assert_eq!(
+            decode_ascii85("87cvR").unwrap_err(),
+            "invalid ascii85: invalid character at offset 3"
+        );
+        assert_eq!(
+            decode_ascii85("87cURD").unwrap_err(),
+            "invalid ascii85: final group of 1 character at offset 5"
+        );
+        assert_eq!(
+            decode_ascii85("uuuuu").unwrap_err(),
+            "invalid ascii85: group overflow at offset 0"
+        );
+    }
+
+    #[test]
+    fn test_z85_spec_vector() {
+        let data = [0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b];
+        assert_eq!(encode_z85(&data).unwrap(), "HelloWorld");
+        assert_eq!(decode_z85("HelloWorld").unwrap(), data);
+    }
+
+    #[test]
+    fn test_z85_length_errors() {
+        assert_eq!(
+            encode_z85(b"abc").unwrap_err(),
+            "invalid z85: input length must be a multiple of 4"
+        );
+        assert_eq!(decode_z85("Hello").unwrap(), [0x86, 0x4f, 0xd2, 0x6f]);
+        assert_eq!(
+            decode_z85("HelloW").unwrap_err(),
+            "invalid z85: input length must be a multiple of 5"
+        );
+        assert_eq!(
+            decode_z85("Hell\"").unwrap_err(),
+            "invalid z85: invalid character at offset 4"
+        );
+    }
+
+    #[test]
+    fn test_base85_git_vectors() {
+        // Expected value from Python's base64.b85encode
+        assert_eq!(encode_base85_git(b"Hello, world!"), "NM&qnZ!92pZ*pv8Ap");
+        assert_eq!(
+            decode_base85_git("NM&qnZ!92pZ*pv8Ap").unwrap(),
+            b"Hello, world!"
+        );
+        assert_eq!(
+            decode_base85_git("NM&q\"").unwrap_err(),
+            "invalid base85: invalid character at offset 4"
+        );
+        assert!(decode_base85_git("NM&qnZ").is_err());
+    }
+
+    #[test]
+    fn test_base85_roundtrip_all_formats() {
+        let data: Vec<u8> = (0..=255u8).chain([0; 8]).collect();
+        for len in 0..data.len() {
+            let data = &data[..len];
+            assert_eq!(decode_ascii85(&encode_ascii85(data)).unwrap(), data);
+            assert_eq!(decode_ascii85(&encode_ascii85_adobe(data)).unwrap(), data);
+            assert_eq!(decode_base85_git(&encode_base85_git(data)).unwrap(), data);
+            if len.is_multiple_of(4) {
+                assert_eq!(decode_z85(&encode_z85(data).unwrap()).unwrap(), data);
+            }
+        }
+    }
+}
@@ -0,0 +1,399 @@
+// Bech32 (BIP-173) and Bech32m (BIP-350) encoding/decoding
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc830a3;
+const MAX_LENGTH: usize = 90;
+const CHECKSUM_LENGTH: usize = 6;
+
+/// Which checksum constant a Bech32 string uses
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bech32Variant {
+    /// BIP-173, used for segwit v0 addresses
+    Bech32,
+    /// BIP-350, used for segwit v1+ addresses
+    Bech32m,
+}
+
+impl Bech32Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Bech32Variant::Bech32 => BECH32_CONST,
+            Bech32Variant::Bech32m => BECH32M_CONST,
+        }
+    }
+}
+
+/// The parts of a decoded Bech32 or Bech32m string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bech32Decoded {
+    hrp: String,
+    words: Vec<u8>,
+    variant: Bech32Variant,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Bech32Decoded {
+    /// The human-readable part, in lowercase
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn hrp(&self) -> String {
+        self.hrp.clone()
+    }
+
+    /// The data part as 5-bit words, without the checksum
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn words(&self) -> Vec<u8> {
+        self.words.clone()
+    }
+
+    /// The checksum variant that was detected
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn variant(&self) -> Bech32Variant {
+        self.variant
+    }
+
+    /// The data part converted from 5-bit words back to bytes
+    pub fn data(&self) -> Result<Vec<u8>, String> {
+        bech32_from_words(&self.words)
+    }
+}
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ value as u32;
+        for (i, gen) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    hrp.iter()
+        .map(|c| c >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.iter().map(|c| c & 0x1f))
+}
+
+fn create_checksum(hrp: &[u8], words: &[u8], variant: Bech32Variant) -> [u8; CHECKSUM_LENGTH] {
+    let values = hrp_expand(hrp)
+        .chain(words.iter().copied())
+        .chain([0u8; CHECKSUM_LENGTH]);
+    let modulus = polymod(values) ^ variant.constant();
+    let mut checksum = [0u8; CHECKSUM_LENGTH];
+    for (i, word) in checksum.iter_mut().enumerate() {
+        *word = ((modulus >> (5 * (5 - i))) & 0x1f) as u8;
+    }
+    checksum
+}
+
+/// Check the HRP characters and length, returning it in lowercase
+fn validate_hrp(hrp: &str) -> Result<String, String> {
+    if hrp.is_empty() || hrp.len() > 83 {
+        return Err("invalid bech32: human-readable part must be 1 to 83 characters".to_string());
+    }
+    if let Some(index) = hrp.bytes().position(|c| !(33..=126).contains(&c)) {
+        return Err(format!(
+            "invalid bech32: human-readable part has invalid character at offset {}",
+            index
+        ));
+    }
+    let has_lower = hrp.bytes().any(|c| c.is_ascii_lowercase());
+    let has_upper = hrp.bytes().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err("invalid bech32: mixed case".to_string());
+    }
+    Ok(hrp.to_ascii_lowercase())
+}
+
+/// Regroup a bit stream from `from`-bit groups into `to`-bit groups
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, String> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max_value: u32 = (1 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return Err(format!(
+                "invalid bech32: value {} does not fit in {} bits",
+                value, from
+            ));
+        }
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max_value) != 0 {
+        return Err("invalid bech32: non-zero padding in 5-bit data".to_string());
+    }
+    Ok(out)
+}
+
+/// Convert bytes into 5-bit words, padding the last word with zero bits
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bech32_to_words(data: &[u8]) -> Vec<u8> {
+    convert_bits(data, 8, 5, true).expect("8-bit input always converts")
+}
+
+/// Convert 5-bit words back into bytes
+/// Fails if a word is out of range or the padding bits are not zero
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bech32_from_words(words: &[u8]) -> Result<Vec<u8>, String> {
+    convert_bits(words, 5, 8, false)
+}
+
+/// Encode an HRP and 5-bit words into a Bech32 or Bech32m string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_bech32_words(
+    hrp: &str,
+    words: &[u8],
+    variant: Bech32Variant,
+) -> Result<String, String> {
+    let hrp = validate_hrp(hrp)?;
+    if let Some(word) = words.iter().find(|&&w| w > 31) {
+        return Err(format!(
+            "invalid bech32: word {} does not fit in 5 bits",
+            word
+        ));
+    }
+    if hrp.len() + 1 + words.len() + CHECKSUM_LENGTH > MAX_LENGTH {
+        return Err(format!(
+            "invalid bech32: encoded length exceeds {} characters",
+            MAX_LENGTH
+        ));
+    }
+    let checksum = create_checksum(hrp.as_bytes(), words, variant);
+    let mut encoded = hrp;
+    encoded.push('1');
+    encoded.extend(
+        words
+            .iter()
+            .chain(checksum.iter())
+            .map(|&w| CHARSET[w as usize] as char),
+    );
+    Ok(encoded)
+}
+
+/// Encode an HRP and bytes into a Bech32 (BIP-173) string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_bech32(hrp: &str, data: &[u8]) -> Result<String, String> {
+    encode_bech32_words(hrp, &bech32_to_words(data), Bech32Variant::Bech32)
+}
+
+/// Encode an HRP and bytes into a Bech32m (BIP-350) string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_bech32m(hrp: &str, data: &[u8]) -> Result<String, String> {
+    encode_bech32_words(hrp, &bech32_to_words(data), Bech32Variant::Bech32m)
+}
+
+/// Decode a Bech32 or Bech32m string
+/// Validates the HRP, the character set, the case and the checksum, and
+/// reports which of the two variants the checksum matched.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_bech32(encoded: &str) -> Result<Bech32Decoded, String> {
+    if encoded.len() > MAX_LENGTH {
+        return Err(format!(
+            "invalid bech32: length exceeds {} characters",
+            MAX_LENGTH
+        ));
+    }
+    let has_lower = encoded.bytes().any(|c| c.is_ascii_lowercase());
+    let has_upper = encoded.bytes().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err("invalid bech32: mixed case".to_string());
+    }
+    let separator = encoded
+        .rfind('1')
+        .ok_or_else(|| "invalid bech32: missing separator".to_string())?;
+    let (hrp, data_part) = (&encoded[..separator], &encoded[separator + 1..]);
+    let hrp = validate_hrp(hrp)?;
+    if data_part.len() < CHECKSUM_LENGTH {
+        return Err("invalid bech32: checksum too short".to_string());
+    }
+
+    let mut words = Vec::with_capacity(data_part.len());
+    for (i, c) in data_part.bytes().enumerate() {
+        let word = CHARSET
+            .iter()
+            .position(|&x| x == c.to_ascii_lowercase())
+            .ok_or_else(|| {
+                format!(
+                    "invalid bech32: invalid character at offset {}",
+                    separator + 1 + i
+                )
+            })?;
+        words.push(word as u8);
+    }
+
+    let variant = match polymod(hrp_expand(hrp.as_bytes()).chain(words.iter().copied())) {
+        BECH32_CONST => Bech32Variant::Bech32,
+        BECH32M_CONST => Bech32Variant::Bech32m,
+        _ => return Err("invalid bech32: checksum mismatch".to_string()),
+    };
+    words.truncate(words.len() - CHECKSUM_LENGTH);
+
+    Ok(Bech32Decoded {
+        hrp,
+        words,
+        variant,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_hex;
+
+    #[test]
+    fn test_bip173_valid_bech32() {
+        let vectors = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+        for vector in vectors {
+            let decoded = decode_bech32(vector).unwrap();
+            assert_eq!(decoded.variant(), Bech32Variant::Bech32, "{}", vector);
+            let reencoded =
+                encode_bech32_words(&decoded.hrp(), &decoded.words(), decoded.variant()).unwrap();
+            assert_eq!(reencoded, vector.to_ascii_lowercase());
+        }
+    }
+
+    #[test]
+    fn test_bip350_valid_bech32m() {
+        let vectors = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "11llllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllludsr8",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for vector in vectors {
+            let decoded = decode_bech32(vector).unwrap();
+            assert_eq!(decoded.variant(), Bech32Variant::Bech32m, "{}", vector);
+            let reencoded =
+                encode_bech32_words(&decoded.hrp(), &decoded.words(), decoded.variant()).unwrap();
+            assert_eq!(reencoded, vector.to_ascii_lowercase());
+        }
+    }
+
+    #[test]
+    fn test_bip173_invalid() {
+        let vectors = [
+            "\u{20}1nwldj5",
+            "\u{7f}1axkwrx",
+            "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
+            "pzry9x0s0muk",
+            "1pzry9x0s0muk",
+            "x1b4n0q5v",
+            "li1dgmt3",
+            "A1G7SGD8",
+            "10a06t8",
+            "1qzzfhee",
+        ];
+        for vector in vectors {
+            assert!(decode_bech32(vector).is_err(), "{}", vector);
+        }
+    }
+
+    #[test]
+    fn test_decode_bech32_errors() {
+        assert_eq!(
+            decode_bech32("A12uEL5L").unwrap_err(),
+            "invalid bech32: mixed case"
+        );
+        assert_eq!(
+            decode_bech32("pzry9x0s0muk").unwrap_err(),
+            "invalid bech32: missing separator"
+        );
+        assert_eq!(
+            decode_bech32("x1b4n0q5v").unwrap_err(),
+            "invalid bech32: invalid character at offset 2"
+        );
+        assert_eq!(
+            decode_bech32("a12uel5m").unwrap_err(),
+            "invalid bech32: checksum mismatch"
+        );
+    }
+
+    #[test]
+    fn test_segwit_v0_address() {
+        // BIP-173 P2WPKH example: witness version 0 followed by the program
+        let program = decode_hex("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let mut words = vec![0u8];
+        words.extend(bech32_to_words(&program));
+        let address = encode_bech32_words("bc", &words, Bech32Variant::Bech32).unwrap();
+        assert_eq!(address, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+
+        let decoded = decode_bech32(&address.to_ascii_uppercase()).unwrap();
+        assert_eq!(decoded.hrp(), "bc");
+        assert_eq!(decoded.variant(), Bech32Variant::Bech32);
+        assert_eq!(decoded.words()[0], 0);
+        assert_eq!(bech32_from_words(&decoded.words()[1..]).unwrap(), program);
+    }
+
+    #[test]
+    fn test_bech32_bytes_roundtrip() {
+        let data = b"Hello, bech32!";
+        let encoded = encode_bech32("test", data).unwrap();
+        let decoded = decode_bech32(&encoded).unwrap();
+        assert_eq!(decoded.variant(), Bech32Variant::Bech32);
+        assert_eq!(decoded.data().unwrap(), data);
+
+        let encoded = encode_bech32m("test", data).unwrap();
+        let decoded = decode_bech32(&encoded).unwrap();
+        assert_eq!(decoded.variant(), Bech32Variant::Bech32m);
+        assert_eq!(decoded.data().unwrap(), data);
+    }
+
+    #[test]
+    fn test_encode_bech32_validates_hrp() {
+        assert!(encode_bech32("", b"data").is_err());
+        assert!(encode_bech32("bC", b"data").is_err());
+        assert!(encode_bech32("b c", b"data").is_err());
+        assert_eq!(
+            encode_bech32("BC", &[]).unwrap(),
+            encode_bech32("bc", &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_bech32_rejects_too_long() {
+        assert!(encode_bech32("bc", &[0u8; 60]).is_err());
+    }
+
+    #[test]
+    fn test_bech32_from_words_rejects_bad_padding() {
+        // 0x01 leaves one non-zero padding bit after converting two words
+        assert!(bech32_from_words(&[0, 1]).is_err());
+        assert!(bech32_from_words(&[32]).is_err());
+        assert_eq!(
+            bech32_from_words(&bech32_to_words(&[0xff])).unwrap(),
+            [0xff]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+// Uppercase, formatted and constant-time hex
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Encode a byte slice into an uppercase hex string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_hex_upper(data: &[u8]) -> String {
+    hex::encode_upper(data)
+}
+
+/// Encode a byte slice into hex with a separator between groups
+/// `group_size` is the number of bytes per group, so `(":", 1)` gives
+/// fingerprints like `aa:bb:cc` and `(" ", 4)` gives `aabbccdd eeff`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_hex_formatted(
+    data: &[u8],
+    separator: &str,
+    group_size: usize,
+    uppercase: bool,
+) -> Result<String, String> {
+    if group_size == 0 {
+        return Err("group size must be at least 1".to_string());
+    }
+    let groups: Vec<String> = data
+        .chunks(group_size)
+        .map(|group| {
+            if uppercase {
+                hex::encode_upper(group)
+            } else {
+                hex::encode(group)
+            }
+        })
+        .collect();
+    Ok(groups.join(separator))
+}
+
+/// Map a nibble to its hex digit without branches or table lookups
+/// `alpha_offset` is `b'a' - 10` or `b'A' - 10`.
+fn nibble_to_hex_ct(nibble: u8, alpha_offset: u8) -> u8 {
+    let n = nibble as i32;
+    // -1 when n < 10, 0 otherwise
+    let is_digit = (n - 10) >> 8;
+    let offset = alpha_offset as i32 + (is_digit & (b'0' as i32 - alpha_offset as i32));
+    (n + offset) as u8
+}
+
+/// Mask that is -1 when `lo <= c <= hi` and 0 otherwise, without branches
+fn range_mask_ct(c: i32, lo: i32, hi: i32) -> i32 {
+    ((lo - 1 - c) & (c - hi - 1)) >> 8
+}
+
+/// Encode a byte slice into hex in constant time
+/// Use this for private keys and other secrets: the running time and memory
+/// access pattern do not depend on the data.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_hex_ct(data: &[u8], uppercase: bool) -> String {
+    let alpha_offset = if uppercase { b'A' - 10 } else { b'a' - 10 };
+    let mut out = Vec::with_capacity(data.len() * 2);
+    for &byte in data {
+        out.push(nibble_to_hex_ct(byte >> 4, alpha_offset));
+        out.push(nibble_to_hex_ct(byte & 0x0f, alpha_offset));
+    }
+    String::from_utf8(out).expect("hex digits are ASCII")
+}
+
+/// Decode a hex string (either case) into a byte vector in constant time
+/// The whole input is always processed; the error does not say where the
+/// first invalid character was, since that would depend on the secret.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_hex_ct(encoded: &str) -> Result<Vec<u8>, String> {
+    let bytes = encoded.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return Err("invalid hex".to_string());
+    }
+    let mut out = Vec::with_capacity(bytes.len() / 2);
+    let mut invalid = 0i32;
+    for pair in bytes.chunks_exact(2) {
+        let mut byte = 0i32;
+        for &c in pair {
+            let c = c as i32;
+            let digit = range_mask_ct(c, b'0' as i32, b'9' as i32);
+            let lower = range_mask_ct(c, b'a' as i32, b'f' as i32);
+            let upper = range_mask_ct(c, b'A' as i32, b'F' as i32);
+            let value = (digit & (c - b'0' as i32))
+                | (lower & (c - (b'a' as i32 - 10)))
+                | (upper & (c - (b'A' as i32 - 10)));
+            invalid |= !(digit | lower | upper);
+            byte = (byte << 4) | value;
+        }
+        out.push(byte as u8);
+    }
+    if invalid & 1 != 0 {
+        return Err("invalid hex".to_string());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_hex, encode_hex};
+
+    #[test]
+    fn test_encode_hex_upper() {
+        assert_eq!(
+            encode_hex_upper(b"Hello, world!"),
+            "48656C6C6F2C20776F726C6421"
+        );
+        assert_eq!(
+            decode_hex("48656C6C6F2C20776F726C6421").unwrap(),
+            b"Hello, world!"
+        );
+    }
+
+    #[test]
+    fn test_encode_hex_formatted() {
+        let data = [0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+        assert_eq!(
+            encode_hex_formatted(&data, ":", 1, false).unwrap(),
+            "aa:bb:cc:dd:ee:ff"
+        );
+        assert_eq!(
+            encode_hex_formatted(&data, ":", 1, true).unwrap(),
+            "AA:BB:CC:DD:EE:FF"
+        );
+        assert_eq!(
+            encode_hex_formatted(&data, " ", 4, false).unwrap(),
+            "aabbccdd eeff"
+        );
+        assert_eq!(
+            encode_hex_formatted(&data, "", 2, false).unwrap(),
+            encode_hex(&data)
+        );
+        assert_eq!(encode_hex_formatted(&[], ":", 1, false).unwrap(), "");
+        assert!(encode_hex_formatted(&data, ":", 0, false).is_err());
+    }
+
+    #[test]
+    fn test_encode_hex_ct_matches_hex_crate() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        assert_eq!(encode_hex_ct(&data, false), encode_hex(&data));
+        assert_eq!(encode_hex_ct(&data, true), encode_hex_upper(&data));
+    }
+
+    #[test]
+    fn test_decode_hex_ct_matches_hex_crate() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        assert_eq!(decode_hex_ct(&encode_hex(&data)).unwrap(), data);
+        assert_eq!(decode_hex_ct(&encode_hex_upper(&data)).unwrap(), data);
+        assert_eq!(decode_hex_ct("aBcD").unwrap(), [0xab, 0xcd]);
+    }
+
+    #[test]
+    fn test_decode_hex_ct_rejects_every_invalid_character() {
+        for c in 0..=255u8 {
+            let encoded = [b'0', c];
+            let expected = decode_hex(std::str::from_utf8(&encoded).unwrap_or("zz"));
+            let result = decode_hex_ct(std::str::from_utf8(&encoded).unwrap_or("zz"));
+            assert_eq!(result.is_ok(), expected.is_ok(), "character {}", c);
+        }
+        assert_eq!(decode_hex_ct("abc").unwrap_err(), "invalid hex");
+        assert_eq!(decode_hex_ct("0g").unwrap_err(), "invalid hex");
+    }
+}
@@ -1,129 +1,372 @@
-use base64::{engine::general_purpose as lib_base64, Engine};
-use hex::{decode as lib_hex_decode, encode as lib_hex_encode};
+use base64::{
+    alphabet as lib_base64_alphabet,
+    engine::{
+        general_purpose as lib_base64, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig,
+    },
+    DecodeError as Base64DecodeError, DecodeSliceError as Base64DecodeSliceError, Engine,
+};
+use hex::{decode_to_slice as lib_hex_decode_to_slice, encode_to_slice as lib_hex_encode_to_slice};
 use base32::{Alphabet as Base32Alphabet, decode as lib_base32_decode, encode as lib_base32_encode};
+use bs58::decode::Error as Base58DecodeError;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+mod base45;
+mod base64_strict;
+mod base85;
+mod bech32;
+mod hex_format;
+mod multibase;
+mod simd;
+mod stream;
+mod text;
+
+pub use base45::{decode_base45, encode_base45, encoded_len_base45};
+pub use base64_strict::{decode_base64_mime, decode_base64_strict, Base64Error, Base64ErrorKind};
+pub use base85::{
+    decode_ascii85, decode_base85_git, decode_z85, encode_ascii85, encode_ascii85_adobe,
+    encode_base85_git, encode_z85,
+};
+pub use bech32::{
+    bech32_from_words, bech32_to_words, decode_bech32, encode_bech32, encode_bech32_words,
+    encode_bech32m, Bech32Decoded, Bech32Variant,
+};
+pub use hex_format::{decode_hex_ct, encode_hex_ct, encode_hex_formatted, encode_hex_upper};
+pub use multibase::{decode_multibase, encode_multibase, Multibase, MultibaseDecoded};
+pub use stream::{
+    Base32Decoder, Base32Encoder, Base32Encoding, Base64Decoder, Base64Encoder, HexDecoder,
+    HexEncoder,
+};
+pub use text::{
+    decode_utf16be, decode_utf16be_lossy, decode_utf16le, decode_utf16le_lossy, decode_utf8,
+    decode_utf8_lossy, encode_utf16be, encode_utf16le, encode_utf8, is_valid_utf8,
+};
+
 /// Remove whitespace (spaces, tabs, newlines) from the input string
 fn strip_whitespace(input: &str) -> String {
     input.chars().filter(|c| !c.is_whitespace()).collect()
 }
 
 /// Encode a byte slice into a base64 string using the default engine
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_base64(data: &[u8]) -> String {
-    lib_base64::STANDARD.encode(data)
+    simd::encode_base64(data)
 }
 
 /// Decode a base64 string into a byte vector
 /// Returns an error string if decoding fails
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base64_strip_whitespace(encoded: &str) -> Result<Vec<u8>, String> {
    let stripped_encoded = strip_whitespace(encoded);
-    lib_base64::STANDARD
-        .decode(&stripped_encoded)
-        .map_err(|_| "invalid base64".to_string())
+    simd::decode_base64(stripped_encoded.as_bytes())
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base64(encoded: &str) -> Result<Vec<u8>, String> {
-    lib_base64::STANDARD
-        .decode(encoded)
-        .map_err(|_| "invalid base64".to_string())
+    simd::decode_base64(encoded.as_bytes())
 }
 
 /// Encode a byte slice into a hex string
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_hex(data: &[u8]) -> String {
-    lib_hex_encode(data)
+    simd::encode_hex(data)
 }
 
 /// Decode a hex string into a byte vector
 /// Returns an error string if decoding fails
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_hex(encoded: &str) -> Result<Vec<u8>, String> {
-    lib_hex_decode(encoded).map_err(|_| "invalid hex".to_string())
+    simd::decode_hex(encoded)
+}
+
+// Encoding/decoding into caller-provided buffers
+//
+// These write into `out` instead of allocating and return the number of bytes
+// written. `out` may be larger than needed; size it with the length helpers.
+
+/// Number of characters `encode_hex` produces for `len` bytes
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encoded_len_hex(len: usize) -> Result<usize, String> {
+    len.checked_mul(2)
+        .ok_or_else(|| "input too large".to_string())
+}
+
+/// Number of bytes `decode_hex` produces for `len` characters of valid hex
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decoded_len_upper_bound_hex(len: usize) -> usize {
+    len / 2
+}
+
+/// Number of characters `encode_base64` produces for `len` bytes
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encoded_len_base64(len: usize) -> Result<usize, String> {
+    base64::encoded_len(len, true).ok_or_else(|| "input too large".to_string())
+}
+
+/// Upper bound on the bytes `decode_base64` produces for `len` characters
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decoded_len_upper_bound_base64(len: usize) -> usize {
+    base64::decoded_len_estimate(len)
+}
+
+/// Encode a byte slice as hex into `out`, returning the number of bytes written
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_hex_into(data: &[u8], out: &mut [u8]) -> Result<usize, String> {
+    let len = encoded_len_hex(data.len())?;
+    let out = out
+        .get_mut(..len)
+        .ok_or_else(|| "output buffer too small".to_string())?;
+    lib_hex_encode_to_slice(data, out).map_err(|_| "output buffer too small".to_string())?;
+    Ok(len)
+}
+
+/// Decode a hex string into `out`, returning the number of bytes written
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_hex_into(encoded: &str, out: &mut [u8]) -> Result<usize, String> {
+    let len = decoded_len_upper_bound_hex(encoded.len());
+    let out = out
+        .get_mut(..len)
+        .ok_or_else(|| "output buffer too small".to_string())?;
+    lib_hex_decode_to_slice(encoded, out).map_err(|_| "invalid hex".to_string())?;
+    Ok(len)
+}
+
+/// Encode a byte slice as base64 into `out`, returning the number of bytes written
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_base64_into(data: &[u8], out: &mut [u8]) -> Result<usize, String> {
+    lib_base64::STANDARD
+        .encode_slice(data, out)
+        .map_err(|_| "output buffer too small".to_string())
+}
+
+/// Decode a base64 string into `out`, returning the number of bytes written
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base64_into(encoded: &str, out: &mut [u8]) -> Result<usize, String> {
+    lib_base64::STANDARD
+        .decode_slice(encoded, out)
+        .map_err(|err| match err {
+            Base64DecodeSliceError::OutputSliceTooSmall => "output buffer too small".to_string(),
+            Base64DecodeSliceError::DecodeError(_) => "invalid base64".to_string(),
+        })
 }
 
 // Base32 encoding/decoding functions
 
 /// Encode a byte slice into a Crockford base32 string
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_base32_crockford(data: &[u8]) -> String {
     lib_base32_encode(Base32Alphabet::Crockford, data)
 }
 
 /// Decode a Crockford base32 string into a byte vector
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base32_crockford(encoded: &str) -> Result<Vec<u8>, String> {
     lib_base32_decode(Base32Alphabet::Crockford, encoded)
         .ok_or_else(|| "invalid base32 crockford".to_string())
 }
 
 /// Encode a byte slice into an RFC4648 base32 string
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_base32_rfc4648(data: &[u8], padding: bool) -> String {
     lib_base32_encode(Base32Alphabet::Rfc4648 { padding }, data)
 }
 
 /// Decode an RFC4648 base32 string into a byte vector
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base32_rfc4648(encoded: &str, padding: bool) -> Result<Vec<u8>, String> {
     lib_base32_decode(Base32Alphabet::Rfc4648 { padding }, encoded)
         .ok_or_else(|| "invalid base32 rfc4648".to_string())
 }
 
 /// Encode a byte slice into an RFC4648 lowercase base32 string
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_base32_rfc4648_lower(data: &[u8], padding: bool) -> String {
     lib_base32_encode(Base32Alphabet::Rfc4648Lower { padding }, data)
 }
 
 /// Decode an RFC4648 lowercase base32 string into a byte vector
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base32_rfc4648_lower(encoded: &str, padding: bool) -> Result<Vec<u8>, String> {
     lib_base32_decode(Base32Alphabet::Rfc4648Lower { padding }, encoded)
         .ok_or_else(|| "invalid base32 rfc4648 lower".to_string())
 }
 
 /// Encode a byte slice into an RFC4648 hex base32 string
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_base32_rfc4648_hex(data: &[u8], padding: bool) -> String {
     lib_base32_encode(Base32Alphabet::Rfc4648Hex { padding }, data)
 }
 
 /// Decode an RFC4648 hex base32 string into a byte vector
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base32_rfc4648_hex(encoded: &str, padding: bool) -> Result<Vec<u8>, String> {
     lib_base32_decode(Base32Alphabet::Rfc4648Hex { padding }, encoded)
         .ok_or_else(|| "invalid base32 rfc4648 hex".to_string())
 }
 
 /// Encode a byte slice into an RFC4648 hex lowercase base32 string
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_base32_rfc4648_hex_lower(data: &[u8], padding: bool) -> String {
     lib_base32_encode(Base32Alphabet::Rfc4648HexLower { padding }, data)
 }
 
 /// Decode an RFC4648 hex lowercase base32 string into a byte vector
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base32_rfc4648_hex_lower(encoded: &str, padding: bool) -> Result<Vec<u8>, String> {
     lib_base32_decode(Base32Alphabet::Rfc4648HexLower { padding }, encoded)
         .ok_or_else(|| "invalid base32 rfc4648 hex lower".to_string())
 }
 
 /// Encode a byte slice into a z-base-32 string
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn encode_base32_z(data: &[u8]) -> String {
     lib_base32_encode(Base32Alphabet::Z, data)
 }
 
 /// Decode a z-base-32 string into a byte vector
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn decode_base32_z(encoded: &str) -> Result<Vec<u8>, String> {
     lib_base32_decode(Base32Alphabet::Z, encoded)
         .ok_or_else(|| "invalid base32 z".to_string())
 }
 
+// Base64url encoding/decoding functions (RFC 4648 §5)
+
+/// URL-safe engine that accepts input with or without padding
+const URL_SAFE_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
+    &lib_base64_alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Map a base64 decode error to a message that says what was wrong
+fn base64url_strict_error(err: Base64DecodeError, padding: bool) -> String {
+    match err {
+        Base64DecodeError::InvalidByte(offset, b'+' | b'/') => {
+            format!(
+                "invalid base64url: standard alphabet character at offset {}",
+                offset
+            )
+        }
+        Base64DecodeError::InvalidByte(offset, b'=') => {
+            format!("invalid base64url: misplaced padding at offset {}", offset)
+        }
+        Base64DecodeError::InvalidByte(offset, _) => {
+            format!("invalid base64url: invalid character at offset {}", offset)
+        }
+        Base64DecodeError::InvalidLength(_) => "invalid base64url: invalid length".to_string(),
+        Base64DecodeError::InvalidLastSymbol(offset, _) => {
+            format!(
+                "invalid base64url: non-canonical trailing bits at offset {}",
+                offset
+            )
+        }
+        Base64DecodeError::InvalidPadding if padding => {
+            "invalid base64url: missing or malformed padding".to_string()
+        }
+        Base64DecodeError::InvalidPadding => "invalid base64url: unexpected padding".to_string(),
+    }
+}
+
+/// Encode a byte slice into a padded base64url string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_base64url(data: &[u8]) -> String {
+    lib_base64::URL_SAFE.encode(data)
+}
+
+/// Encode a byte slice into an unpadded base64url string, as used by JWS/JWT
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_base64url_no_pad(data: &[u8]) -> String {
+    lib_base64::URL_SAFE_NO_PAD.encode(data)
+}
+
+/// Decode a base64url string into a byte vector
+/// Padding is optional, but if present it must be correct
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base64url(encoded: &str) -> Result<Vec<u8>, String> {
+    URL_SAFE_INDIFFERENT
+        .decode(encoded)
+        .map_err(|_| "invalid base64url".to_string())
+}
+
+/// Decode an unpadded base64url string into a byte vector
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base64url_no_pad(encoded: &str) -> Result<Vec<u8>, String> {
+    lib_base64::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|_| "invalid base64url".to_string())
+}
+
+/// Decode a base64url string, requiring canonical form
+/// Rejects the standard alphabet (`+`, `/`), padding that does not match
+/// `padding`, and non-zero trailing bits. The error says which rule failed.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base64url_strict(encoded: &str, padding: bool) -> Result<Vec<u8>, String> {
+    let engine = if padding {
+        lib_base64::URL_SAFE
+    } else {
+        lib_base64::URL_SAFE_NO_PAD
+    };
+    engine
+        .decode(encoded)
+        .map_err(|err| base64url_strict_error(err, padding))
+}
+
+// Base58 encoding/decoding functions (Bitcoin alphabet)
+
+/// Map a base58 decode error to a message that says what was wrong
+fn base58_error(err: Base58DecodeError, name: &str) -> String {
+    match err {
+        Base58DecodeError::InvalidCharacter { character, index } => {
+            format!(
+                "invalid {}: invalid character '{}' at offset {}",
+                name, character, index
+            )
+        }
+        Base58DecodeError::NonAsciiCharacter { index } => {
+            format!("invalid {}: non-ascii character at offset {}", name, index)
+        }
+        Base58DecodeError::InvalidChecksum { .. } => {
+            format!("invalid {}: checksum mismatch", name)
+        }
+        Base58DecodeError::NoChecksum => {
+            format!("invalid {}: too short to contain a checksum", name)
+        }
+        _ => format!("invalid {}", name),
+    }
+}
+
+/// Encode a byte slice into a base58 string using the Bitcoin alphabet
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_base58(data: &[u8]) -> String {
+    bs58::encode(data).into_string()
+}
+
+/// Decode a base58 string (Bitcoin alphabet) into a byte vector
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base58(encoded: &str) -> Result<Vec<u8>, String> {
+    bs58::decode(encoded)
+        .into_vec()
+        .map_err(|err| base58_error(err, "base58"))
+}
+
+/// Encode a byte slice into a base58check string
+/// Appends the first 4 bytes of the double SHA-256 of `data` before encoding.
+/// For addresses, `data` should already start with the version byte.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_base58check(data: &[u8]) -> String {
+    bs58::encode(data).with_check().into_string()
+}
+
+/// Decode a base58check string into a byte vector
+/// Verifies and strips the 4-byte double SHA-256 checksum.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_base58check(encoded: &str) -> Result<Vec<u8>, String> {
+    bs58::decode(encoded)
+        .with_check(None)
+        .into_vec()
+        .map_err(|err| base58_error(err, "base58check"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,4 +525,240 @@ mod tests {
         let decoded = decode_base32_z(&encoded).unwrap();
         assert_eq!(decoded, input);
     }
+
+    // Base64url tests
+
+    #[test]
+    fn test_base64url_rfc4648_vectors() {
+        // RFC 4648 §10 vectors, which are identical in both alphabets
+        let vectors: [(&[u8], &str, &str); 7] = [
+            (b"", "", ""),
+            (b"f", "Zg==", "Zg"),
+            (b"fo", "Zm8=", "Zm8"),
+            (b"foo", "Zm9v", "Zm9v"),
+            (b"foob", "Zm9vYg==", "Zm9vYg"),
+            (b"fooba", "Zm9vYmE=", "Zm9vYmE"),
+            (b"foobar", "Zm9vYmFy", "Zm9vYmFy"),
+        ];
+        for (data, padded, unpadded) in vectors {
+            assert_eq!(encode_base64url(data), padded);
+            assert_eq!(encode_base64url_no_pad(data), unpadded);
+            assert_eq!(decode_base64url(padded).unwrap(), data);
+            assert_eq!(decode_base64url(unpadded).unwrap(), data);
+            assert_eq!(decode_base64url_no_pad(unpadded).unwrap(), data);
+            assert_eq!(decode_base64url_strict(padded, true).unwrap(), data);
+            assert_eq!(decode_base64url_strict(unpadded, false).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_base64url_uses_url_safe_alphabet() {
+        let input = [0xfb, 0xff, 0xbf];
+        assert_eq!(encode_base64(&input), "+/+/");
+        assert_eq!(encode_base64url(&input), "-_-_");
+        assert_eq!(decode_base64url("-_-_").unwrap(), input);
+    }
+
+    #[test]
+    fn test_base64url_jwt_header() {
+        let header = br#"{"alg":"HS256","typ":"JWT"}"#;
+        let encoded = encode_base64url_no_pad(header);
+        assert_eq!(encoded, "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9");
+        assert_eq!(decode_base64url_strict(&encoded, false).unwrap(), header);
+    }
+
+    #[test]
+    fn test_decode_base64url_rejects_standard_alphabet() {
+        assert_eq!(decode_base64url("+/+/").unwrap_err(), "invalid base64url");
+        assert_eq!(
+            decode_base64url_strict("ab+/", false).unwrap_err(),
+            "invalid base64url: standard alphabet character at offset 2"
+        );
+    }
+
+    #[test]
+    fn test_decode_base64url_no_pad_rejects_padding() {
+        assert!(decode_base64url_no_pad("Zg==").is_err());
+        assert_eq!(
+            decode_base64url_strict("Zg==", false).unwrap_err(),
+            "invalid base64url: unexpected padding"
+        );
+    }
+
+    #[test]
+    fn test_decode_base64url_strict_requires_padding() {
+        assert_eq!(
+            decode_base64url_strict("Zg", true).unwrap_err(),
+            "invalid base64url: missing or malformed padding"
+        );
+        assert!(decode_base64url_strict("Zg=", true).is_err());
+    }
+
+    #[test]
+    fn test_decode_base64url_rejects_non_canonical_trailing_bits() {
+        // "Zh" decodes to 'f' but leaves a non-zero trailing bit
+        assert!(decode_base64url("Zh").is_err());
+        assert!(decode_base64url_no_pad("Zh").is_err());
+        assert_eq!(
+            decode_base64url_strict("Zh", false).unwrap_err(),
+            "invalid base64url: non-canonical trailing bits at offset 1"
+        );
+    }
+
+    #[test]
+    fn test_decode_base64url_strict_invalid_length() {
+        assert_eq!(
+            decode_base64url_strict("Z", false).unwrap_err(),
+            "invalid base64url: invalid length"
+        );
+    }
+
+    // Base58 tests
+
+    #[test]
+    fn test_base58_vectors() {
+        // Vectors from the Bitcoin Core base58_encode_decode.json suite
+        let vectors: [(&str, &str); 8] = [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("636363", "aPEr"),
+            (
+                "73696d706c792061206c6f6e6720737472696e67",
+                "2cFupjhnEsSn59qHXstmK2ffpLv2",
+            ),
+            (
+                "00eb15231dfceb60925886b67d065299925915aeb172c06647",
+                "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+            ),
+            ("000000287fb4cd", "111233QC4"),
+            ("00000000000000000000", "1111111111"),
+        ];
+        for (hex, encoded) in vectors {
+            let data = decode_hex(hex).unwrap();
+            assert_eq!(encode_base58(&data), encoded);
+            assert_eq!(decode_base58(encoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_base58_invalid_character() {
+        // '0', 'O', 'I' and 'l' are not in the Bitcoin alphabet
+        assert_eq!(
+            decode_base58("1O1").unwrap_err(),
+            "invalid base58: invalid character 'O' at offset 1"
+        );
+        assert!(decode_base58("0").is_err());
+        assert!(decode_base58("l").is_err());
+    }
+
+    #[test]
+    fn test_base58check_bitcoin_address() {
+        // P2PKH address for hash160 010966776006953d5567439e5e39f86a0d273bee
+        let payload = decode_hex("00010966776006953d5567439e5e39f86a0d273bee").unwrap();
+        let address = "16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM";
+        assert_eq!(encode_base58check(&payload), address);
+        assert_eq!(decode_base58check(address).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_base58check_roundtrip() {
+        for size in [0, 1, 20, 21, 32, 33, 65] {
+            let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+            let encoded = encode_base58check(&data);
+            assert_eq!(decode_base58check(&encoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_base58check_bad_checksum() {
+        // Last character changed from 'M' to 'N'
+        assert_eq!(
+            decode_base58check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvN").unwrap_err(),
+            "invalid base58check: checksum mismatch"
+        );
+    }
+
+    #[test]
+    fn test_decode_base58check_too_short() {
+        let short = encode_base58(&[0x01, 0x02, 0x03]);
+        assert_eq!(
+            decode_base58check(&short).unwrap_err(),
+            "invalid base58check: too short to contain a checksum"
+        );
+    }
+
+    // Caller-provided buffer tests
+
+    #[test]
+    fn test_encode_hex_into() {
+        let data = b"Hello, world!";
+        let mut out = vec![0u8; encoded_len_hex(data.len()).unwrap() + 4];
+        let written = encode_hex_into(data, &mut out).unwrap();
+        assert_eq!(written, 26);
+        assert_eq!(&out[..written], encode_hex(data).as_bytes());
+        assert_eq!(&out[written..], [0u8; 4]);
+    }
+
+    #[test]
+    fn test_decode_hex_into() {
+        let encoded = "48656c6c6f2c20776f726c6421";
+        let mut out = vec![0u8; decoded_len_upper_bound_hex(encoded.len())];
+        let written = decode_hex_into(encoded, &mut out).unwrap();
+        assert_eq!(&out[..written], b"Hello, world!");
+    }
+
+    #[test]
+    fn test_hex_into_errors() {
+        let mut small = [0u8; 3];
+        assert_eq!(
+            encode_hex_into(b"ab", &mut small).unwrap_err(),
+            "output buffer too small"
+        );
+        assert_eq!(
+            decode_hex_into("00112233", &mut small).unwrap_err(),
+            "output buffer too small"
+        );
+        assert_eq!(
+            decode_hex_into("abc", &mut small).unwrap_err(),
+            "invalid hex"
+        );
+        assert_eq!(
+            decode_hex_into("zz", &mut small).unwrap_err(),
+            "invalid hex"
+        );
+    }
+
+    #[test]
+    fn test_base64_into_roundtrip() {
+        for len in 0..20 {
+            let data: Vec<u8> = (0..len).map(|i| i as u8 * 7).collect();
+            let mut encoded = vec![0u8; encoded_len_base64(len).unwrap()];
+            let written = encode_base64_into(&data, &mut encoded).unwrap();
+            assert_eq!(written, encoded.len());
+            let encoded = std::str::from_utf8(&encoded).unwrap();
+            assert_eq!(encoded, encode_base64(&data));
+
+            let mut decoded = vec![0u8; decoded_len_upper_bound_base64(encoded.len())];
+            let written = decode_base64_into(encoded, &mut decoded).unwrap();
+            assert_eq!(&decoded[..written], data);
+        }
+    }
+
+    #[test]
+    fn test_base64_into_errors() {
+        let mut small = [0u8; 3];
+        assert_eq!(
+            encode_base64_into(b"abcd", &mut small).unwrap_err(),
+            "output buffer too small"
+        );
+        assert_eq!(
+            decode_base64_into("SGVsbG8=", &mut small).unwrap_err(),
+            "output buffer too small"
+        );
+        assert_eq!(
+            decode_base64_into("invalid_base64", &mut [0u8; 16]).unwrap_err(),
+            "invalid base64"
+        );
+    }
 }
@@ -0,0 +1,255 @@
+// Multibase: self-describing strings whose first character names the encoding
+
+use crate::{
+    decode_base32_rfc4648, decode_base32_rfc4648_hex, decode_base32_rfc4648_hex_lower,
+    decode_base32_rfc4648_lower, decode_base32_z, decode_base58, decode_base64, decode_base64url,
+    decode_hex, encode_base32_rfc4648, encode_base32_rfc4648_hex, encode_base32_rfc4648_hex_lower,
+    encode_base32_rfc4648_lower, encode_base32_z, encode_base58, encode_base64, encode_base64url,
+    encode_base64url_no_pad, encode_hex, encode_hex_upper,
+};
+use base64::{engine::general_purpose as lib_base64, Engine};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// The multibase encodings this crate can produce and parse
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Multibase {
+    /// `f`: lowercase hex
+    Base16,
+    /// `F`: uppercase hex
+    Base16Upper,
+    /// `b`: RFC 4648 base32, lowercase, no padding
+    Base32,
+    /// `B`: RFC 4648 base32, uppercase, no padding
+    Base32Upper,
+    /// `c`: RFC 4648 base32, lowercase, padded
+    Base32Pad,
+    /// `C`: RFC 4648 base32, uppercase, padded
+    Base32PadUpper,
+    /// `v`: RFC 4648 base32hex, lowercase, no padding
+    Base32Hex,
+    /// `V`: RFC 4648 base32hex, uppercase, no padding
+    Base32HexUpper,
+    /// `t`: RFC 4648 base32hex, lowercase, padded
+    Base32HexPad,
+    /// `T`: RFC 4648 base32hex, uppercase, padded
+    Base32HexPadUpper,
+    /// `h`: z-base-32
+    Base32Z,
+    /// `z`: base58 with the Bitcoin alphabet
+    Base58Btc,
+    /// `m`: standard base64, no padding
+    Base64,
+    /// `M`: standard base64, padded
+    Base64Pad,
+    /// `u`: base64url, no padding
+    Base64Url,
+    /// `U`: base64url, padded
+    Base64UrlPad,
+}
+
+const ALL_CODECS: [Multibase; 16] = [
+    Multibase::Base16,
+    Multibase::Base16Upper,
+    Multibase::Base32,
+    Multibase::Base32Upper,
+    Multibase::Base32Pad,
+    Multibase::Base32PadUpper,
+    Multibase::Base32Hex,
+    Multibase::Base32HexUpper,
+    Multibase::Base32HexPad,
+    Multibase::Base32HexPadUpper,
+    Multibase::Base32Z,
+    Multibase::Base58Btc,
+    Multibase::Base64,
+    Multibase::Base64Pad,
+    Multibase::Base64Url,
+    Multibase::Base64UrlPad,
+];
+
+impl Multibase {
+    /// The prefix character from the multibase table
+    pub fn prefix(self) -> char {
+        match self {
+            Multibase::Base16 => 'f',
+            Multibase::Base16Upper => 'F',
+            Multibase::Base32 => 'b',
+            Multibase::Base32Upper => 'B',
+            Multibase::Base32Pad => 'c',
+            Multibase::Base32PadUpper => 'C',
+            Multibase::Base32Hex => 'v',
+            Multibase::Base32HexUpper => 'V',
+            Multibase::Base32HexPad => 't',
+            Multibase::Base32HexPadUpper => 'T',
+            Multibase::Base32Z => 'h',
+            Multibase::Base58Btc => 'z',
+            Multibase::Base64 => 'm',
+            Multibase::Base64Pad => 'M',
+            Multibase::Base64Url => 'u',
+            Multibase::Base64UrlPad => 'U',
+        }
+    }
+
+    /// Look up the codec for a prefix character
+    pub fn from_prefix(prefix: char) -> Option<Multibase> {
+        ALL_CODECS
+            .into_iter()
+            .find(|codec| codec.prefix() == prefix)
+    }
+
+    fn encode(self, data: &[u8]) -> String {
+        match self {
+            Multibase::Base16 => encode_hex(data),
+            Multibase::Base16Upper => encode_hex_upper(data),
+            Multibase::Base32 => encode_base32_rfc4648_lower(data, false),
+            Multibase::Base32Upper => encode_base32_rfc4648(data, false),
+            Multibase::Base32Pad => encode_base32_rfc4648_lower(data, true),
+            Multibase::Base32PadUpper => encode_base32_rfc4648(data, true),
+            Multibase::Base32Hex => encode_base32_rfc4648_hex_lower(data, false),
+            Multibase::Base32HexUpper => encode_base32_rfc4648_hex(data, false),
+            Multibase::Base32HexPad => encode_base32_rfc4648_hex_lower(data, true),
+            Multibase::Base32HexPadUpper => encode_base32_rfc4648_hex(data, true),
+            Multibase::Base32Z => encode_base32_z(data),
+            Multibase::Base58Btc => encode_base58(data),
+            Multibase::Base64 => lib_base64::STANDARD_NO_PAD.encode(data),
+            Multibase::Base64Pad => encode_base64(data),
+            Multibase::Base64Url => encode_base64url_no_pad(data),
+            Multibase::Base64UrlPad => encode_base64url(data),
+        }
+    }
+
+    fn decode(self, encoded: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Multibase::Base16 | Multibase::Base16Upper => decode_hex(encoded),
+            Multibase::Base32 => decode_base32_rfc4648_lower(encoded, false),
+            Multibase::Base32Upper => decode_base32_rfc4648(encoded, false),
+            Multibase::Base32Pad => decode_base32_rfc4648_lower(encoded, true),
+            Multibase::Base32PadUpper => decode_base32_rfc4648(encoded, true),
+            Multibase::Base32Hex => decode_base32_rfc4648_hex_lower(encoded, false),
+            Multibase::Base32HexUpper => decode_base32_rfc4648_hex(encoded, false),
+            Multibase::Base32HexPad => decode_base32_rfc4648_hex_lower(encoded, true),
+            Multibase::Base32HexPadUpper => decode_base32_rfc4648_hex(encoded, true),
+            Multibase::Base32Z => decode_base32_z(encoded),
+            Multibase::Base58Btc => decode_base58(encoded),
+            Multibase::Base64 => lib_base64::STANDARD_NO_PAD
+                .decode(encoded)
+                .map_err(|_| "invalid base64".to_string()),
+            Multibase::Base64Pad => decode_base64(encoded),
+            Multibase::Base64Url | Multibase::Base64UrlPad => decode_base64url(encoded),
+        }
+    }
+}
+
+/// The result of decoding a multibase string
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultibaseDecoded {
+    codec: Multibase,
+    data: Vec<u8>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl MultibaseDecoded {
+    /// The codec named by the prefix
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn codec(&self) -> Multibase {
+        self.codec
+    }
+
+    /// The decoded bytes
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn data(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+/// Encode a byte slice as a multibase string with the given codec
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_multibase(codec: Multibase, data: &[u8]) -> String {
+    let mut encoded = String::from(codec.prefix());
+    encoded.push_str(&codec.encode(data));
+    encoded
+}
+
+/// Decode a multibase string, detecting the codec from its prefix
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_multibase(encoded: &str) -> Result<MultibaseDecoded, String> {
+    let prefix = encoded
+        .chars()
+        .next()
+        .ok_or_else(|| "invalid multibase: empty string".to_string())?;
+    let codec = Multibase::from_prefix(prefix)
+        .ok_or_else(|| format!("invalid multibase: unsupported prefix '{}'", prefix))?;
+    let data = codec.decode(&encoded[prefix.len_utf8()..])?;
+    Ok(MultibaseDecoded { codec, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multibase_spec_vectors() {
+        // "yes mani !" from the multibase test suite
+        let data = b"yes mani !";
+        let vectors = [
+            (Multibase::Base16, "f796573206d616e692021"),
+            (Multibase::Base16Upper, "F796573206D616E692021"),
+            (Multibase::Base32, "bpfsxgidnmfxgsibb"),
+            (Multibase::Base32Upper, "BPFSXGIDNMFXGSIBB"),
+            (Multibase::Base32Pad, "cpfsxgidnmfxgsibb"),
+            (Multibase::Base32PadUpper, "CPFSXGIDNMFXGSIBB"),
+            (Multibase::Base32Hex, "vf5in683dc5n6i811"),
+            (Multibase::Base32HexUpper, "VF5IN683DC5N6I811"),
+            (Multibase::Base32HexPad, "tf5in683dc5n6i811"),
+            (Multibase::Base32HexPadUpper, "TF5IN683DC5N6I811"),
+            (Multibase::Base32Z, "hxf1zgedpcfzg1ebb"),
+            (Multibase::Base58Btc, "z7paNL19xttacUY"),
+            (Multibase::Base64, "meWVzIG1hbmkgIQ"),
+            (Multibase::Base64Pad, "MeWVzIG1hbmkgIQ=="),
+            (Multibase::Base64Url, "ueWVzIG1hbmkgIQ"),
+            (Multibase::Base64UrlPad, "UeWVzIG1hbmkgIQ=="),
+        ];
+        for (codec, encoded) in vectors {
+            assert_eq!(encode_multibase(codec, data), encoded, "{:?}", codec);
+            let decoded = decode_multibase(encoded).unwrap();
+            assert_eq!(decoded.codec(), codec);
+            assert_eq!(decoded.data(), data);
+        }
+    }
+
+    #[test]
+    fn test_multibase_padded_base32() {
+        let encoded = encode_multibase(Multibase::Base32Pad, b"f");
+        assert_eq!(encoded, "cmy======");
+        assert_eq!(decode_multibase(&encoded).unwrap().data(), b"f");
+    }
+
+    #[test]
+    fn test_multibase_roundtrip_all_codecs() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        for len in [0, 1, 2, 3, 4, 5, 31, 32, 33, 256] {
+            for codec in ALL_CODECS {
+                let encoded = encode_multibase(codec, &data[..len]);
+                let decoded = decode_multibase(&encoded).unwrap();
+                assert_eq!(decoded.codec(), codec);
+                assert_eq!(decoded.data(), &data[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_multibase_errors() {
+        assert_eq!(
+            decode_multibase("").unwrap_err(),
+            "invalid multibase: empty string"
+        );
+        assert_eq!(
+            decode_multibase("Qabc").unwrap_err(),
+            "invalid multibase: unsupported prefix 'Q'"
+        );
+        assert_eq!(decode_multibase("fzz").unwrap_err(), "invalid hex");
+        assert!(decode_multibase("z0OIl").is_err());
+    }
+}
@@ -0,0 +1,325 @@
+// Vectorised hex and base64 for wasm32 simd128
+//
+// Chosen at build time: with `-C target-feature=+simd128` on wasm32 the
+// functions below process 16-byte blocks with `core::arch::wasm32`, otherwise
+// they are the scalar `hex` and `base64` crates. Both paths produce identical
+// output and errors. The SIMD code only handles whole blocks of valid input
+// and leaves the tail, padding and every error to the scalar crates.
+
+use base64::{engine::general_purpose as lib_base64, Engine};
+
+// On the SIMD path the scalar encoders are only used by the tests
+#[cfg_attr(
+    all(target_arch = "wasm32", target_feature = "simd128"),
+    allow(dead_code)
+)]
+pub(crate) mod scalar {
+    use super::*;
+
+    pub(crate) fn encode_hex(data: &[u8]) -> String {
+        hex::encode(data)
+    }
+
+    pub(crate) fn decode_hex(encoded: &str) -> Result<Vec<u8>, String> {
+        hex::decode(encoded).map_err(|_| "invalid hex".to_string())
+    }
+
+    pub(crate) fn encode_base64(data: &[u8]) -> String {
+        lib_base64::STANDARD.encode(data)
+    }
+
+    pub(crate) fn decode_base64(encoded: &[u8]) -> Result<Vec<u8>, String> {
+        lib_base64::STANDARD
+            .decode(encoded)
+            .map_err(|_| "invalid base64".to_string())
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm32 {
+    use super::*;
+    use core::arch::wasm32::*;
+
+    fn load(bytes: &[u8]) -> v128 {
+        assert!(bytes.len() >= 16);
+        // SAFETY: at least 16 readable bytes; v128_load allows unaligned reads
+        unsafe { v128_load(bytes.as_ptr() as *const v128) }
+    }
+
+    fn store(out: &mut [u8], v: v128) {
+        assert!(out.len() >= 16);
+        // SAFETY: at least 16 writable bytes; v128_store allows unaligned writes
+        unsafe { v128_store(out.as_mut_ptr() as *mut v128, v) }
+    }
+
+    /// Map each byte (0..64) to its base64 character
+    fn base64_symbols(indices: v128) -> v128 {
+        // swizzle yields 0 for lane indices of 16 or more, so each table
+        // only contributes for its own range of 16 values
+        let tables: [&[u8; 16]; 4] = [
+            b"ABCDEFGHIJKLMNOP",
+            b"QRSTUVWXYZabcdef",
+            b"ghijklmnopqrstuv",
+            b"wxyz0123456789+/",
+        ];
+        let mut symbols = u8x16_splat(0);
+        for (i, table) in tables.iter().enumerate() {
+            let local = u8x16_sub(indices, u8x16_splat(16 * i as u8));
+            symbols = v128_or(symbols, i8x16_swizzle(load(&table[..]), local));
+        }
+        symbols
+    }
+
+    /// Map base64 characters to their 6-bit values
+    /// Returns `None` if any lane is not in the alphabet (including `=`).
+    fn base64_values(symbols: v128) -> Option<v128> {
+        // (first, count, add): symbols in first..first + count map to symbol + add
+        let ranges: [(u8, u8, u8); 5] = [
+            (b'A', 26, 0u8.wrapping_sub(b'A')),
+            (b'a', 26, 26u8.wrapping_sub(b'a')),
+            (b'0', 10, 52u8.wrapping_sub(b'0')),
+            (b'+', 1, 62u8.wrapping_sub(b'+')),
+            (b'/', 1, 63u8.wrapping_sub(b'/')),
+        ];
+        let mut values = u8x16_splat(0);
+        let mut valid = u8x16_splat(0);
+        for (first, count, add) in ranges {
+            let in_range = u8x16_lt(u8x16_sub(symbols, u8x16_splat(first)), u8x16_splat(count));
+            let mapped = u8x16_add(symbols, u8x16_splat(add));
+            values = v128_bitselect(mapped, values, in_range);
+            valid = v128_or(valid, in_range);
+        }
+        u8x16_all_true(valid).then_some(values)
+    }
+
+    /// Map hex characters (either case) to their nibble values
+    /// Returns `None` if any lane is not a hex digit.
+    fn hex_values(symbols: v128) -> Option<v128> {
+        let digit = u8x16_sub(symbols, u8x16_splat(b'0'));
+        let is_digit = u8x16_lt(digit, u8x16_splat(10));
+        // Setting 0x20 lowercases letters and leaves digits alone
+        let alpha = u8x16_sub(v128_or(symbols, u8x16_splat(0x20)), u8x16_splat(b'a'));
+        let is_alpha = u8x16_lt(alpha, u8x16_splat(6));
+        if !u8x16_all_true(v128_or(is_digit, is_alpha)) {
+            return None;
+        }
+        let alpha = u8x16_add(alpha, u8x16_splat(10));
+        Some(v128_bitselect(digit, alpha, is_digit))
+    }
+
+    pub(crate) fn encode_hex(data: &[u8]) -> String {
+        let digits = load(b"0123456789abcdef");
+        let mut out = vec![0u8; data.len() * 2];
+        let blocks = data.len() / 16;
+        for i in 0..blocks {
+            let v = load(&data[i * 16..]);
+            let hi = i8x16_swizzle(digits, u8x16_shr(v, 4));
+            let lo = i8x16_swizzle(digits, v128_and(v, u8x16_splat(0x0f)));
+            // Interleave the high and low digits of each byte
+            let first =
+                u8x16_shuffle::<0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23>(hi, lo);
+            let second =
+                u8x16_shuffle::<8, 24, 9, 25, 10, 26, 11, 27, 12, 28, 13, 29, 14, 30, 15, 31>(
+                    hi, lo,
+                );
+            store(&mut out[i * 32..], first);
+            store(&mut out[i * 32 + 16..], second);
+        }
+        hex::encode_to_slice(&data[blocks * 16..], &mut out[blocks * 32..])
+            .expect("output sized for the tail");
+        String::from_utf8(out).expect("hex digits are ASCII")
+    }
+
+    pub(crate) fn decode_hex(encoded: &str) -> Result<Vec<u8>, String> {
+        let bytes = encoded.as_bytes();
+        if !bytes.len().is_multiple_of(2) {
+            return scalar::decode_hex(encoded);
+        }
+        let mut out = vec![0u8; bytes.len() / 2];
+        let blocks = bytes.len() / 32;
+        for i in 0..blocks {
+            let (Some(a), Some(b)) = (
+                hex_values(load(&bytes[i * 32..])),
+                hex_values(load(&bytes[i * 32 + 16..])),
+            ) else {
+                return scalar::decode_hex(encoded);
+            };
+            // Split into high and low nibbles, then combine each pair
+            let hi =
+                u8x16_shuffle::<0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30>(a, b);
+            let lo =
+                u8x16_shuffle::<1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31>(a, b);
+            store(&mut out[i * 16..], v128_or(u8x16_shl(hi, 4), lo));
+        }
+        hex::decode_to_slice(&bytes[blocks * 32..], &mut out[blocks * 16..])
+            .map_err(|_| "invalid hex".to_string())?;
+        Ok(out)
+    }
+
+    pub(crate) fn encode_base64(data: &[u8]) -> String {
+        let mut out = Vec::with_capacity(data.len().div_ceil(3) * 4);
+        // Each step reads 16 bytes but only encodes the first 12
+        let mut pos = 0;
+        while data.len() - pos >= 16 {
+            // [a, b, c] -> [b, a, c, b], so the 16-bit lanes hold "ab" and "bc"
+            let v = i8x16_swizzle(
+                load(&data[pos..]),
+                u8x16(1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10),
+            );
+            // "ab" lanes yield the first two 6-bit indices, "bc" lanes the last two
+            let ab = v128_or(
+                v128_and(u16x8_shr(v, 10), u16x8_splat(0x003f)),
+                v128_and(u16x8_shl(v, 4), u16x8_splat(0x3f00)),
+            );
+            let bc = v128_or(
+                v128_and(u16x8_shr(v, 6), u16x8_splat(0x003f)),
+                v128_and(u16x8_shl(v, 8), u16x8_splat(0x3f00)),
+            );
+            let indices = v128_bitselect(ab, bc, u32x4_splat(0x0000ffff));
+            let mut block = [0u8; 16];
+            store(&mut block, base64_symbols(indices));
+            out.extend_from_slice(&block);
+            pos += 12;
+        }
+        // pos is a multiple of 3, so the tail encodes independently
+        out.extend_from_slice(lib_base64::STANDARD.encode(&data[pos..]).as_bytes());
+        String::from_utf8(out).expect("base64 alphabet is ASCII")
+    }
+
+    pub(crate) fn decode_base64(encoded: &[u8]) -> Result<Vec<u8>, String> {
+        let mut out = Vec::with_capacity(encoded.len() / 4 * 3);
+        let mut pos = 0;
+        // Stop before the last block so the scalar decoder always sees the
+        // final quantum and checks its padding
+        while encoded.len() - pos > 16 {
+            let Some(values) = base64_values(load(&encoded[pos..])) else {
+                break;
+            };
+            // Pack pairs of 6-bit values into 12-bit lanes, then pairs of
+            // those into the 24 bits of each group
+            let pairs = v128_or(
+                v128_and(u16x8_shl(values, 6), u16x8_splat(0x0fc0)),
+                u16x8_shr(values, 8),
+            );
+            let groups = v128_or(
+                v128_and(u32x4_shl(pairs, 12), u32x4_splat(0x00fff000)),
+                u32x4_shr(pairs, 16),
+            );
+            // Each group's three bytes, most significant first
+            let bytes = i8x16_swizzle(
+                groups,
+                u8x16(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, 255, 255, 255, 255),
+            );
+            let mut block = [0u8; 16];
+            store(&mut block, bytes);
+            out.extend_from_slice(&block[..12]);
+            pos += 16;
+        }
+        out.extend_from_slice(&scalar::decode_base64(&encoded[pos..])?);
+        Ok(out)
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+pub(crate) use wasm32::{decode_base64, decode_hex, encode_base64, encode_hex};
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+pub(crate) use scalar::{decode_base64, decode_hex, encode_base64, encode_hex};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random bytes (xorshift)
+    fn test_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    // Lengths around the 12, 16 and 32-byte block sizes
+    const LENGTHS: [usize; 20] = [
+        0, 1, 2, 3, 11, 12, 13, 15, 16, 17, 23, 24, 31, 32, 33, 47, 48, 64, 100, 1000,
+    ];
+
+    #[test]
+    fn test_hex_matches_scalar() {
+        for len in LENGTHS {
+            let data = test_bytes(len, len as u64);
+            let encoded = encode_hex(&data);
+            assert_eq!(encoded, scalar::encode_hex(&data), "len {}", len);
+            assert_eq!(decode_hex(&encoded).unwrap(), data, "len {}", len);
+            let upper = encoded.to_uppercase();
+            assert_eq!(
+                decode_hex(&upper),
+                scalar::decode_hex(&upper),
+                "len {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_base64_matches_scalar() {
+        for len in LENGTHS {
+            let data = test_bytes(len, len as u64 + 1);
+            let encoded = encode_base64(&data);
+            assert_eq!(encoded, scalar::encode_base64(&data), "len {}", len);
+            assert_eq!(
+                decode_base64(encoded.as_bytes()).unwrap(),
+                data,
+                "len {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_hex_every_byte_value() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        assert_eq!(encode_hex(&data), scalar::encode_hex(&data));
+        assert_eq!(decode_hex(&scalar::encode_hex(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_hex_invalid_input_matches_scalar() {
+        let valid = scalar::encode_hex(&test_bytes(40, 7));
+        for pos in 0..valid.len() {
+            for c in [b'g', b'G', b'/', b':', b'@', b'`', b' ', 0x80] {
+                let mut bytes = valid.clone().into_bytes();
+                bytes[pos] = c;
+                let encoded = String::from_utf8_lossy(&bytes).into_owned();
+                assert_eq!(decode_hex(&encoded), scalar::decode_hex(&encoded));
+            }
+        }
+        assert_eq!(decode_hex(&valid[1..]), scalar::decode_hex(&valid[1..]));
+    }
+
+    #[test]
+    fn test_decode_base64_invalid_input_matches_scalar() {
+        let valid = scalar::encode_base64(&test_bytes(50, 9));
+        for pos in 0..valid.len() {
+            for c in [b'=', b'-', b'_', b' ', b'.', b'@', b'[', b'`', b'{', 0x80] {
+                let mut bytes = valid.clone().into_bytes();
+                bytes[pos] = c;
+                assert_eq!(decode_base64(&bytes), scalar::decode_base64(&bytes));
+            }
+        }
+        // Every byte value in every lane of a block
+        for c in 0..=255u8 {
+            let mut bytes = valid.clone().into_bytes();
+            bytes[5] = c;
+            assert_eq!(decode_base64(&bytes), scalar::decode_base64(&bytes));
+        }
+        for len in 0..valid.len() {
+            let bytes = &valid.as_bytes()[..len];
+            assert_eq!(decode_base64(bytes), scalar::decode_base64(bytes));
+        }
+    }
+}
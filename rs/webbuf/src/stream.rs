@@ -0,0 +1,494 @@
+// Streaming (chunked) hex, base64 and base32 encoders and decoders
+//
+// Each type buffers the partial group left over at the end of a chunk and
+// carries it into the next call, so the concatenated output of `update` and
+// `finish` is identical to the one-shot functions in the crate root.
+
+use base32::Alphabet as Base32Alphabet;
+use base64::{engine::general_purpose as lib_base64, Engine};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// The base32 alphabets supported by the streaming base32 types
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base32Encoding {
+    Crockford,
+    Rfc4648,
+    Rfc4648Lower,
+    Rfc4648Hex,
+    Rfc4648HexLower,
+    Z,
+}
+
+impl Base32Encoding {
+    fn alphabet(self, padding: bool) -> Base32Alphabet {
+        match self {
+            Base32Encoding::Crockford => Base32Alphabet::Crockford,
+            Base32Encoding::Rfc4648 => Base32Alphabet::Rfc4648 { padding },
+            Base32Encoding::Rfc4648Lower => Base32Alphabet::Rfc4648Lower { padding },
+            Base32Encoding::Rfc4648Hex => Base32Alphabet::Rfc4648Hex { padding },
+            Base32Encoding::Rfc4648HexLower => Base32Alphabet::Rfc4648HexLower { padding },
+            Base32Encoding::Z => Base32Alphabet::Z,
+        }
+    }
+
+    /// Whether encoded output ends with `=` padding
+    fn is_padded(self, padding: bool) -> bool {
+        padding && !matches!(self, Base32Encoding::Crockford | Base32Encoding::Z)
+    }
+
+    fn error(self) -> String {
+        match self {
+            Base32Encoding::Crockford => "invalid base32 crockford",
+            Base32Encoding::Rfc4648 => "invalid base32 rfc4648",
+            Base32Encoding::Rfc4648Lower => "invalid base32 rfc4648 lower",
+            Base32Encoding::Rfc4648Hex => "invalid base32 rfc4648 hex",
+            Base32Encoding::Rfc4648HexLower => "invalid base32 rfc4648 hex lower",
+            Base32Encoding::Z => "invalid base32 z",
+        }
+        .to_string()
+    }
+}
+
+/// Split `pending` at the largest multiple of `group` bytes, keeping the
+/// remainder in `pending` and returning the complete groups
+fn take_groups(pending: &mut Vec<u8>, group: usize) -> Vec<u8> {
+    let complete = pending.len() - pending.len() % group;
+    let rest = pending.split_off(complete);
+    std::mem::replace(pending, rest)
+}
+
+/// Chunked hex encoder
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Default)]
+pub struct HexEncoder {}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HexEncoder {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> HexEncoder {
+        HexEncoder {}
+    }
+
+    /// Encode a chunk of bytes
+    pub fn update(&mut self, chunk: &[u8]) -> String {
+        hex::encode(chunk)
+    }
+
+    /// Finish the stream. Hex has no partial groups, so this is always empty
+    pub fn finish(&mut self) -> String {
+        String::new()
+    }
+}
+
+/// Chunked hex decoder
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Default)]
+pub struct HexDecoder {
+    pending: Vec<u8>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HexDecoder {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> HexDecoder {
+        HexDecoder::default()
+    }
+
+    /// Decode a chunk of hex, holding back a trailing odd character
+    pub fn update(&mut self, chunk: &str) -> Result<Vec<u8>, String> {
+        self.pending.extend_from_slice(chunk.as_bytes());
+        let complete = take_groups(&mut self.pending, 2);
+        hex::decode(complete).map_err(|_| "invalid hex".to_string())
+    }
+
+    /// Finish the stream, failing if an odd character is left over
+    pub fn finish(&mut self) -> Result<Vec<u8>, String> {
+        if !std::mem::take(&mut self.pending).is_empty() {
+            return Err("invalid hex".to_string());
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Chunked base64 encoder (standard alphabet, padded)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Default)]
+pub struct Base64Encoder {
+    pending: Vec<u8>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Base64Encoder {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> Base64Encoder {
+        Base64Encoder::default()
+    }
+
+    /// Encode a chunk, holding back up to two bytes that do not fill a group
+    pub fn update(&mut self, chunk: &[u8]) -> String {
+        self.pending.extend_from_slice(chunk);
+        let complete = take_groups(&mut self.pending, 3);
+        lib_base64::STANDARD.encode(complete)
+    }
+
+    /// Encode the held-back bytes with padding and reset the encoder
+    pub fn finish(&mut self) -> String {
+        lib_base64::STANDARD.encode(std::mem::take(&mut self.pending))
+    }
+}
+
+/// Chunked base64 decoder (standard alphabet, padded)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Default)]
+pub struct Base64Decoder {
+    pending: Vec<u8>,
+    strip_whitespace: bool,
+    padded: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Base64Decoder {
+    /// Create a decoder. With `strip_whitespace`, Unicode whitespace anywhere
+    /// in the input is ignored, exactly as `decode_base64_strip_whitespace`
+    /// ignores it
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(strip_whitespace: bool) -> Base64Decoder {
+        Base64Decoder {
+            strip_whitespace,
+            ..Base64Decoder::default()
+        }
+    }
+
+    /// Decode a chunk, holding back characters that do not fill a group
+    pub fn update(&mut self, chunk: &str) -> Result<Vec<u8>, String> {
+        if self.strip_whitespace {
+            for part in chunk.split(char::is_whitespace) {
+                self.pending.extend_from_slice(part.as_bytes());
+            }
+        } else {
+            self.pending.extend_from_slice(chunk.as_bytes());
+        }
+        if self.padded && !self.pending.is_empty() {
+            return Err("invalid base64: data after padding".to_string());
+        }
+        let complete = take_groups(&mut self.pending, 4);
+        if complete.last() == Some(&b'=') {
+            self.padded = true;
+        }
+        lib_base64::STANDARD
+            .decode(complete)
+            .map_err(|_| "invalid base64".to_string())
+    }
+
+    /// Finish the stream, failing if an incomplete group is left over
+    pub fn finish(&mut self) -> Result<Vec<u8>, String> {
+        let pending = std::mem::take(&mut self.pending);
+        self.padded = false;
+        if !pending.is_empty() {
+            return Err("invalid base64".to_string());
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Chunked base32 encoder for any of the supported alphabets
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct Base32Encoder {
+    encoding: Base32Encoding,
+    padding: bool,
+    pending: Vec<u8>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Base32Encoder {
+    /// Create an encoder. `padding` only applies to the RFC 4648 alphabets
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(encoding: Base32Encoding, padding: bool) -> Base32Encoder {
+        Base32Encoder {
+            encoding,
+            padding,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Encode a chunk, holding back up to four bytes that do not fill a group
+    pub fn update(&mut self, chunk: &[u8]) -> String {
+        self.pending.extend_from_slice(chunk);
+        let complete = take_groups(&mut self.pending, 5);
+        base32::encode(self.encoding.alphabet(self.padding), &complete)
+    }
+
+    /// Encode the held-back bytes, with padding if enabled, and reset
+    pub fn finish(&mut self) -> String {
+        let pending = std::mem::take(&mut self.pending);
+        base32::encode(self.encoding.alphabet(self.padding), &pending)
+    }
+}
+
+/// Chunked base32 decoder for any of the supported alphabets
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct Base32Decoder {
+    encoding: Base32Encoding,
+    padding: bool,
+    pending: Vec<u8>,
+    padded: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Base32Decoder {
+    /// Create a decoder. `padding` only applies to the RFC 4648 alphabets
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(encoding: Base32Encoding, padding: bool) -> Base32Decoder {
+        Base32Decoder {
+            encoding,
+            padding,
+            pending: Vec::new(),
+            padded: false,
+        }
+    }
+
+    /// Decode a chunk, holding back characters that do not fill a group
+    pub fn update(&mut self, chunk: &str) -> Result<Vec<u8>, String> {
+        if !chunk.is_ascii() {
+            return Err(self.encoding.error());
+        }
+        self.pending.extend_from_slice(chunk.as_bytes());
+        if self.padded && !self.pending.is_empty() {
+            return Err(format!("{}: data after padding", self.encoding.error()));
+        }
+        let complete = take_groups(&mut self.pending, 8);
+        if complete.last() == Some(&b'=') {
+            self.padded = true;
+        }
+        self.decode(&complete)
+    }
+
+    /// Decode any trailing unpadded group and reset the decoder
+    pub fn finish(&mut self) -> Result<Vec<u8>, String> {
+        let pending = std::mem::take(&mut self.pending);
+        self.padded = false;
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.encoding.is_padded(self.padding) {
+            return Err(self.encoding.error());
+        }
+        self.decode(&pending)
+    }
+}
+
+impl Base32Decoder {
+    fn decode(&self, ascii: &[u8]) -> Result<Vec<u8>, String> {
+        // Input was checked to be ASCII in `update`
+        let text = std::str::from_utf8(ascii).map_err(|_| self.encoding.error())?;
+        base32::decode(self.encoding.alphabet(self.padding), text)
+            .ok_or_else(|| self.encoding.error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode_base64, decode_base64_strip_whitespace, encode_base32_crockford,
+        encode_base32_rfc4648, encode_base64,
+    };
+
+    fn test_data() -> Vec<u8> {
+        (0..1000u32).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_hex_stream_matches_one_shot() {
+        let data = test_data();
+        for chunk_size in [1, 2, 3, 7, 64, 1000] {
+            let mut encoder = HexEncoder::new();
+            let mut encoded = String::new();
+            for chunk in data.chunks(chunk_size) {
+                encoded.push_str(&encoder.update(chunk));
+            }
+            encoded.push_str(&encoder.finish());
+            assert_eq!(encoded, hex::encode(&data));
+
+            let mut decoder = HexDecoder::new();
+            let mut decoded = Vec::new();
+            for chunk in encoded.as_bytes().chunks(chunk_size) {
+                let chunk = std::str::from_utf8(chunk).unwrap();
+                decoded.extend(decoder.update(chunk).unwrap());
+            }
+            decoded.extend(decoder.finish().unwrap());
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn test_hex_decoder_odd_length() {
+        let mut decoder = HexDecoder::new();
+        assert_eq!(decoder.update("abc").unwrap(), [0xab]);
+        assert!(decoder.finish().is_err());
+    }
+
+    #[test]
+    fn test_hex_decoder_invalid() {
+        let mut decoder = HexDecoder::new();
+        assert_eq!(decoder.update("zz").unwrap_err(), "invalid hex");
+    }
+
+    #[test]
+    fn test_base64_stream_matches_one_shot() {
+        let data = test_data();
+        for len in [0, 1, 2, 3, 4, 999, 1000] {
+            let data = &data[..len];
+            for chunk_size in [1, 2, 3, 5, 64, 1000] {
+                let mut encoder = Base64Encoder::new();
+                let mut encoded = String::new();
+                for chunk in data.chunks(chunk_size) {
+                    encoded.push_str(&encoder.update(chunk));
+                }
+                encoded.push_str(&encoder.finish());
+                assert_eq!(encoded, encode_base64(data));
+
+                let mut decoder = Base64Decoder::new(false);
+                let mut decoded = Vec::new();
+                for chunk in encoded.as_bytes().chunks(chunk_size) {
+                    let chunk = std::str::from_utf8(chunk).unwrap();
+                    decoded.extend(decoder.update(chunk).unwrap());
+                }
+                decoded.extend(decoder.finish().unwrap());
+                assert_eq!(decoded, data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_base64_decoder_strip_whitespace() {
+        let mut decoder = Base64Decoder::new(true);
+        let mut decoded = decoder.update("SGVs bG8s\nIHdv").unwrap();
+        decoded.extend(decoder.update(" cmxk IQ==\n").unwrap());
+        decoded.extend(decoder.finish().unwrap());
+        assert_eq!(decoded, decode_base64("SGVsbG8sIHdvcmxkIQ==").unwrap());
+    }
+
+    #[test]
+    fn test_base64_decoder_strips_the_same_whitespace_as_one_shot() {
+        // Vertical tab, no-break space and line separator are whitespace to
+        // char::is_whitespace but not to u8::is_ascii_whitespace
+        for input in ["SGVs\u{0b}bG8=", "SGVs\u{a0}bG8=", "SGVs\u{2028}bG8="] {
+            let mut decoder = Base64Decoder::new(true);
+            let mut decoded = decoder.update(input).unwrap();
+            decoded.extend(decoder.finish().unwrap());
+            assert_eq!(decoded, decode_base64_strip_whitespace(input).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_base64_decoder_rejects_whitespace_by_default() {
+        let mut decoder = Base64Decoder::new(false);
+        assert!(decoder.update("SGVs bG8s").is_err());
+    }
+
+    #[test]
+    fn test_base64_decoder_data_after_padding() {
+        let mut decoder = Base64Decoder::new(false);
+        assert_eq!(decoder.update("Zg==").unwrap(), b"f");
+        assert_eq!(
+            decoder.update("Zg==").unwrap_err(),
+            "invalid base64: data after padding"
+        );
+    }
+
+    #[test]
+    fn test_base64_decoder_truncated() {
+        let mut decoder = Base64Decoder::new(false);
+        assert_eq!(decoder.update("Zm9vYg").unwrap(), b"foo");
+        assert_eq!(decoder.finish().unwrap_err(), "invalid base64");
+    }
+
+    #[test]
+    fn test_base32_stream_matches_one_shot() {
+        let data = test_data();
+        let encodings = [
+            Base32Encoding::Crockford,
+            Base32Encoding::Rfc4648,
+            Base32Encoding::Rfc4648Lower,
+            Base32Encoding::Rfc4648Hex,
+            Base32Encoding::Rfc4648HexLower,
+            Base32Encoding::Z,
+        ];
+        for encoding in encodings {
+            for padding in [true, false] {
+                for len in [0, 1, 4, 5, 6, 999] {
+                    let data = &data[..len];
+                    for chunk_size in [1, 3, 5, 8, 64] {
+                        let mut encoder = Base32Encoder::new(encoding, padding);
+                        let mut encoded = String::new();
+                        for chunk in data.chunks(chunk_size) {
+                            encoded.push_str(&encoder.update(chunk));
+                        }
+                        encoded.push_str(&encoder.finish());
+                        assert_eq!(encoded, base32::encode(encoding.alphabet(padding), data));
+
+                        let mut decoder = Base32Decoder::new(encoding, padding);
+                        let mut decoded = Vec::new();
+                        for chunk in encoded.as_bytes().chunks(chunk_size) {
+                            let chunk = std::str::from_utf8(chunk).unwrap();
+                            decoded.extend(decoder.update(chunk).unwrap());
+                        }
+                        decoded.extend(decoder.finish().unwrap());
+                        assert_eq!(decoded, data);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_base32_encoder_known_values() {
+        let mut encoder = Base32Encoder::new(Base32Encoding::Rfc4648, true);
+        let mut encoded = encoder.update(b"Hello, ");
+        encoded.push_str(&encoder.update(b"world!"));
+        encoded.push_str(&encoder.finish());
+        assert_eq!(encoded, encode_base32_rfc4648(b"Hello, world!", true));
+
+        let mut encoder = Base32Encoder::new(Base32Encoding::Crockford, false);
+        let mut encoded = encoder.update(b"Hello, world!");
+        encoded.push_str(&encoder.finish());
+        assert_eq!(encoded, encode_base32_crockford(b"Hello, world!"));
+    }
+
+    #[test]
+    fn test_base32_decoder_errors() {
+        let mut decoder = Base32Decoder::new(Base32Encoding::Rfc4648, true);
+        assert_eq!(
+            decoder.update("JBSWY3D!").unwrap_err(),
+            "invalid base32 rfc4648"
+        );
+
+        let mut decoder = Base32Decoder::new(Base32Encoding::Rfc4648, true);
+        assert!(decoder.update("JBSW").is_ok());
+        assert_eq!(decoder.finish().unwrap_err(), "invalid base32 rfc4648");
+
+        let mut decoder = Base32Decoder::new(Base32Encoding::Rfc4648, true);
+        assert!(decoder.update("MY======").is_ok());
+        assert_eq!(
+            decoder.update("MY======").unwrap_err(),
+            "invalid base32 rfc4648: data after padding"
+        );
+    }
+
+    #[test]
+    fn test_finish_resets_state() {
+        let mut encoder = Base64Encoder::new();
+        encoder.update(b"f");
+        assert_eq!(encoder.finish(), "Zg==");
+        encoder.update(b"fo");
+        assert_eq!(encoder.finish(), "Zm8=");
+
+        let mut decoder = Base64Decoder::new(false);
+        decoder.update("Zg==").unwrap();
+        decoder.finish().unwrap();
+        assert_eq!(decoder.update("Zm8=").unwrap(), b"fo");
+    }
+}
@@ -0,0 +1,235 @@
+// UTF-8 and UTF-16 text conversion
+//
+// Strict decoders fail on the first malformed sequence and report its byte
+// offset. Lossy decoders replace each malformed sequence with U+FFFD, using
+// the same rules as the WHATWG TextDecoder, so results match the browser.
+// Neither mode strips a leading byte order mark.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Encode a string as UTF-8 bytes
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_utf8(text: &str) -> Vec<u8> {
+    text.as_bytes().to_vec()
+}
+
+/// Check whether a byte slice is valid UTF-8
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn is_valid_utf8(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_ok()
+}
+
+/// Decode UTF-8 bytes, failing on the first malformed sequence
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_utf8(data: &[u8]) -> Result<String, String> {
+    match std::str::from_utf8(data) {
+        Ok(text) => Ok(text.to_string()),
+        // error_len() is None when the input ends partway through a character
+        Err(err) if err.error_len().is_none() => Err(format!(
+            "invalid utf-8: incomplete sequence at offset {}",
+            err.valid_up_to()
+        )),
+        Err(err) => Err(format!(
+            "invalid utf-8: invalid byte sequence at offset {}",
+            err.valid_up_to()
+        )),
+    }
+}
+
+/// Decode UTF-8 bytes, replacing malformed sequences with U+FFFD
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_utf8_lossy(data: &[u8]) -> String {
+    String::from_utf8_lossy(data).into_owned()
+}
+
+fn encode_utf16(text: &str, big_endian: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len() * 2);
+    for unit in text.encode_utf16() {
+        let bytes = if big_endian {
+            unit.to_be_bytes()
+        } else {
+            unit.to_le_bytes()
+        };
+        out.extend_from_slice(&bytes);
+    }
+    out
+}
+
+fn utf16_units(data: &[u8], big_endian: bool) -> impl Iterator<Item = u16> + '_ {
+    data.chunks_exact(2).map(move |pair| {
+        let pair = [pair[0], pair[1]];
+        if big_endian {
+            u16::from_be_bytes(pair)
+        } else {
+            u16::from_le_bytes(pair)
+        }
+    })
+}
+
+fn decode_utf16(data: &[u8], big_endian: bool) -> Result<String, String> {
+    if !data.len().is_multiple_of(2) {
+        return Err("invalid utf-16: odd number of bytes".to_string());
+    }
+    let mut text = String::with_capacity(data.len() / 2);
+    let mut offset = 0;
+    for c in char::decode_utf16(utf16_units(data, big_endian)) {
+        let c =
+            c.map_err(|_| format!("invalid utf-16: unpaired surrogate at offset {}", offset))?;
+        text.push(c);
+        offset += c.len_utf16() * 2;
+    }
+    Ok(text)
+}
+
+fn decode_utf16_lossy(data: &[u8], big_endian: bool) -> String {
+    let mut text: String = char::decode_utf16(utf16_units(data, big_endian))
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    // A dangling final byte is a truncated code unit
+    if !data.len().is_multiple_of(2) {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    text
+}
+
+/// Encode a string as UTF-16LE bytes, without a byte order mark
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_utf16le(text: &str) -> Vec<u8> {
+    encode_utf16(text, false)
+}
+
+/// Encode a string as UTF-16BE bytes, without a byte order mark
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn encode_utf16be(text: &str) -> Vec<u8> {
+    encode_utf16(text, true)
+}
+
+/// Decode UTF-16LE bytes, failing on an odd length or unpaired surrogate
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_utf16le(data: &[u8]) -> Result<String, String> {
+    decode_utf16(data, false)
+}
+
+/// Decode UTF-16BE bytes, failing on an odd length or unpaired surrogate
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_utf16be(data: &[u8]) -> Result<String, String> {
+    decode_utf16(data, true)
+}
+
+/// Decode UTF-16LE bytes, replacing unpaired surrogates and a trailing odd
+/// byte with U+FFFD
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_utf16le_lossy(data: &[u8]) -> String {
+    decode_utf16_lossy(data, false)
+}
+
+/// Decode UTF-16BE bytes, replacing unpaired surrogates and a trailing odd
+/// byte with U+FFFD
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decode_utf16be_lossy(data: &[u8]) -> String {
+    decode_utf16_lossy(data, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_hex, encode_hex};
+
+    #[test]
+    fn test_utf8_roundtrip() {
+        let text = "héllo, 世界 🌍";
+        let encoded = encode_utf8(text);
+        assert_eq!(
+            encode_hex(&encoded),
+            "68c3a96c6c6f2c20e4b896e7958c20f09f8c8d"
+        );
+        assert!(is_valid_utf8(&encoded));
+        assert_eq!(decode_utf8(&encoded).unwrap(), text);
+        assert_eq!(decode_utf8_lossy(&encoded), text);
+    }
+
+    #[test]
+    fn test_decode_utf8_strict_errors() {
+        let invalid = decode_hex("6869ff6869").unwrap();
+        assert!(!is_valid_utf8(&invalid));
+        assert_eq!(
+            decode_utf8(&invalid).unwrap_err(),
+            "invalid utf-8: invalid byte sequence at offset 2"
+        );
+        // Overlong encoding of '/'
+        assert_eq!(
+            decode_utf8(&decode_hex("c0af").unwrap()).unwrap_err(),
+            "invalid utf-8: invalid byte sequence at offset 0"
+        );
+        // Encoded surrogate U+D800
+        assert_eq!(
+            decode_utf8(&decode_hex("61eda080").unwrap()).unwrap_err(),
+            "invalid utf-8: invalid byte sequence at offset 1"
+        );
+        // First two bytes of a three-byte character
+        assert_eq!(
+            decode_utf8(&decode_hex("61e4b8").unwrap()).unwrap_err(),
+            "invalid utf-8: incomplete sequence at offset 1"
+        );
+    }
+
+    #[test]
+    fn test_decode_utf8_lossy_matches_text_decoder() {
+        // Expected output from `new TextDecoder().decode(...)`
+        let cases = [
+            ("6869ff6869", "hi\u{fffd}hi"),
+            ("c0af", "\u{fffd}\u{fffd}"),
+            ("61e4b8", "a\u{fffd}"),
+            ("f09f8c", "\u{fffd}"),
+            ("eda080", "\u{fffd}\u{fffd}\u{fffd}"),
+        ];
+        for (hex, expected) in cases {
+            assert_eq!(decode_utf8_lossy(&decode_hex(hex).unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn test_utf16_roundtrip() {
+        let text = "aé世🌍";
+        let le = encode_utf16le(text);
+        let be = encode_utf16be(text);
+        assert_eq!(encode_hex(&le), "6100e900164e3cd80ddf");
+        assert_eq!(encode_hex(&be), "006100e94e16d83cdf0d");
+        assert_eq!(decode_utf16le(&le).unwrap(), text);
+        assert_eq!(decode_utf16be(&be).unwrap(), text);
+        assert_eq!(decode_utf16le_lossy(&le), text);
+        assert_eq!(decode_utf16be_lossy(&be), text);
+        assert_eq!(decode_utf16le(&[]).unwrap(), "");
+    }
+
+    #[test]
+    fn test_decode_utf16_strict_errors() {
+        assert_eq!(
+            decode_utf16le(&decode_hex("610062").unwrap()).unwrap_err(),
+            "invalid utf-16: odd number of bytes"
+        );
+        // Lone high surrogate after "a" and a surrogate pair
+        assert_eq!(
+            decode_utf16be(&decode_hex("0061d83cdf0dd83c0062").unwrap()).unwrap_err(),
+            "invalid utf-16: unpaired surrogate at offset 6"
+        );
+        // Lone low surrogate
+        assert_eq!(
+            decode_utf16le(&decode_hex("0ddf").unwrap()).unwrap_err(),
+            "invalid utf-16: unpaired surrogate at offset 0"
+        );
+    }
+
+    #[test]
+    fn test_decode_utf16_lossy() {
+        assert_eq!(
+            decode_utf16be_lossy(&decode_hex("0061d83c0062").unwrap()),
+            "a\u{fffd}b"
+        );
+        assert_eq!(
+            decode_utf16le_lossy(&decode_hex("610062").unwrap()),
+            "a\u{fffd}"
+        );
+    }
+}
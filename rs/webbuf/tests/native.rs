@@ -0,0 +1,59 @@
+// Uses webbuf as an ordinary dependency, the way the other webbuf crates do.
+// Runs without the `wasm` feature, so none of this goes through wasm-bindgen.
+
+use webbuf::{
+    decode_base32_crockford, decode_base58check, decode_base64, decode_base64_strict,
+    decode_bech32, decode_hex, decode_multibase, encode_base32_crockford, encode_base58check,
+    encode_base64, encode_bech32, encode_hex, encode_multibase, Base64ErrorKind, Bech32Variant,
+    HexEncoder, Multibase,
+};
+
+#[test]
+fn test_hex_and_base64_roundtrip() {
+    let data: Vec<u8> = (0..=255u8).collect();
+    assert_eq!(decode_hex(&encode_hex(&data)).unwrap(), data);
+    assert_eq!(decode_base64(&encode_base64(&data)).unwrap(), data);
+    assert_eq!(
+        decode_base32_crockford(&encode_base32_crockford(&data)).unwrap(),
+        data
+    );
+}
+
+#[test]
+fn test_errors_are_plain_rust_types() {
+    let err: String = decode_hex("zz").unwrap_err();
+    assert_eq!(err, "invalid hex");
+
+    let err = decode_base64_strict("Zg").unwrap_err();
+    assert_eq!(err.kind(), Base64ErrorKind::InvalidPadding);
+    let boxed: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(
+        boxed.to_string(),
+        "invalid base64: invalid padding at offset 2"
+    );
+}
+
+#[test]
+fn test_checksummed_encodings() {
+    let data = b"webbuf";
+    assert_eq!(decode_base58check(&encode_base58check(data)).unwrap(), data);
+
+    let encoded = encode_bech32("test", data).unwrap();
+    let decoded = decode_bech32(&encoded).unwrap();
+    assert_eq!(decoded.hrp(), "test");
+    assert_eq!(decoded.variant(), Bech32Variant::Bech32);
+    assert_eq!(decoded.data().unwrap(), data);
+}
+
+#[test]
+fn test_multibase_and_streaming() {
+    let encoded = encode_multibase(Multibase::Base58Btc, b"yes mani !");
+    assert_eq!(encoded, "z7paNL19xttacUY");
+    assert_eq!(decode_multibase(&encoded).unwrap().data(), b"yes mani !");
+
+    let mut encoder = HexEncoder::new();
+    let mut out = encoder.update(b"Hello, ");
+    out.push_str(&encoder.update(b"world!"));
+    out.push_str(&encoder.finish());
+    assert_eq!(out, encode_hex(b"Hello, world!"));
+}
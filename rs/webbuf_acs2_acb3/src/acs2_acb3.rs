@@ -0,0 +1,215 @@
+use subtle::ConstantTimeEq;
+use webbuf_aescbc::aescbc::AesCbc;
+use webbuf_blake3::blake3_mac;
+use webbuf_sha256::sha256_hmac;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Encrypt-then-MAC with AES-256-CBC, byte-compatible with @webbuf/acs2 and
+// @webbuf/acb3
+//
+// Layout: MAC (32 bytes) || IV (16 bytes) || AES-CBC ciphertext, where the
+// MAC is computed over IV || ciphertext and keyed with the AES key.
+//
+// ACS2 = AES + CBC + SHA-256 HMAC
+// ACB3 = AES + CBC + Blake3 MAC
+
+const KEY_SIZE: usize = 32;
+const MAC_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+
+type MacFn = fn(&[u8], &[u8]) -> Result<Vec<u8>, String>;
+
+fn etm_encrypt(mac: MacFn, plaintext: &[u8], aes_key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+    if aes_key.len() != KEY_SIZE {
+        return Err("Invalid key size: expected 32 bytes".to_string());
+    }
+    let ciphertext = AesCbc::new(aes_key)?.encrypt(plaintext, iv)?;
+
+    let mut out = Vec::with_capacity(MAC_SIZE + IV_SIZE + ciphertext.len());
+    out.resize(MAC_SIZE, 0);
+    out.extend_from_slice(iv);
+    out.extend_from_slice(&ciphertext);
+    let tag = mac(aes_key, &out[MAC_SIZE..])?;
+    out[..MAC_SIZE].copy_from_slice(&tag);
+    Ok(out)
+}
+
+fn etm_decrypt(mac: MacFn, ciphertext: &[u8], aes_key: &[u8]) -> Result<Vec<u8>, String> {
+    if aes_key.len() != KEY_SIZE {
+        return Err("Invalid key size: expected 32 bytes".to_string());
+    }
+    if ciphertext.len() < MAC_SIZE + IV_SIZE + 16 {
+        return Err("The encrypted data must be at least 256+128+128 bits, which is the length of the Hmac plus the iv plus the smallest encrypted data size".to_string());
+    }
+
+    // Authenticate before touching the ciphertext, so decryption errors
+    // cannot be used as a padding oracle
+    let (tag, iv_and_ciphertext) = ciphertext.split_at(MAC_SIZE);
+    let expected_tag = mac(aes_key, iv_and_ciphertext)?;
+    if !bool::from(tag.ct_eq(&expected_tag)) {
+        return Err("Message authentication failed Hmacs are not equivalent".to_string());
+    }
+
+    let (iv, ciphertext) = iv_and_ciphertext.split_at(IV_SIZE);
+    AesCbc::new(aes_key)?.decrypt(ciphertext, iv)
+}
+
+/// Encrypt with AES-256-CBC and append a SHA-256 HMAC (ACS2)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn acs2_encrypt(plaintext: &[u8], aes_key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+    etm_encrypt(sha256_hmac, plaintext, aes_key, iv)
+}
+
+/// Verify the SHA-256 HMAC, then decrypt with AES-256-CBC (ACS2)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn acs2_decrypt(ciphertext: &[u8], aes_key: &[u8]) -> Result<Vec<u8>, String> {
+    etm_decrypt(sha256_hmac, ciphertext, aes_key)
+}
+
+/// Encrypt with AES-256-CBC and append a Blake3 MAC (ACB3)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn acb3_encrypt(plaintext: &[u8], aes_key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+    etm_encrypt(blake3_mac, plaintext, aes_key, iv)
+}
+
+/// Verify the Blake3 MAC, then decrypt with AES-256-CBC (ACB3)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn acb3_decrypt(ciphertext: &[u8], aes_key: &[u8]) -> Result<Vec<u8>, String> {
+    etm_decrypt(blake3_mac, ciphertext, aes_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+
+    type EncryptFn = fn(&[u8], &[u8], &[u8]) -> Result<Vec<u8>, String>;
+    type DecryptFn = fn(&[u8], &[u8]) -> Result<Vec<u8>, String>;
+
+    const SCHEMES: [(&str, EncryptFn, DecryptFn); 2] = [
+        ("acs2", acs2_encrypt, acs2_decrypt),
+        ("acb3", acb3_encrypt, acb3_decrypt),
+    ];
+
+    const KEY: [u8; 32] = [0x11; 32];
+    const IV: [u8; 16] = [0x22; 16];
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        name: String,
+        key: String,
+        iv: String,
+        pt: String,
+        ct: String,
+    }
+
+    fn hex_to_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("Invalid hex"))
+            .collect()
+    }
+
+    #[test]
+    fn test_vectors_from_ts_packages() {
+        for (scheme, encrypt, decrypt) in SCHEMES {
+            let path = format!("vectors/vectors-{}.json", scheme);
+            let data = fs::read_to_string(path).expect("Unable to read test vectors file");
+            let vectors: Vec<TestVector> =
+                serde_json::from_str(&data).expect("Error parsing JSON test vectors");
+
+            for vector in vectors {
+                let key = hex_to_bytes(&vector.key);
+                let iv = hex_to_bytes(&vector.iv);
+                let plaintext = hex_to_bytes(&vector.pt);
+                let ciphertext = hex_to_bytes(&vector.ct);
+
+                assert_eq!(
+                    encrypt(&plaintext, &key, &iv).unwrap(),
+                    ciphertext,
+                    "{}: {}",
+                    scheme,
+                    vector.name
+                );
+                assert_eq!(
+                    decrypt(&ciphertext, &key).unwrap(),
+                    plaintext,
+                    "{}: {}",
+                    scheme,
+                    vector.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_any_tampered_byte() {
+        for (scheme, encrypt, decrypt) in SCHEMES {
+            let encrypted = encrypt(b"secret message", &KEY, &IV).unwrap();
+            // MAC, IV and ciphertext bytes alike
+            for pos in 0..encrypted.len() {
+                let mut tampered = encrypted.clone();
+                tampered[pos] ^= 0x01;
+                assert_eq!(
+                    decrypt(&tampered, &KEY).unwrap_err(),
+                    "Message authentication failed Hmacs are not equivalent",
+                    "{}: byte {}",
+                    scheme,
+                    pos
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_wrong_key() {
+        for (scheme, encrypt, decrypt) in SCHEMES {
+            let encrypted = encrypt(b"secret message", &KEY, &IV).unwrap();
+            let mut wrong_key = KEY;
+            wrong_key[0] ^= 0x01;
+            assert!(
+                decrypt(&encrypted, &wrong_key)
+                    .unwrap_err()
+                    .starts_with("Message authentication failed"),
+                "{}",
+                scheme
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_lengths() {
+        for (scheme, encrypt, decrypt) in SCHEMES {
+            let encrypted = encrypt(b"test message", &KEY, &IV).unwrap();
+            assert_eq!(encrypted.len(), 64, "{}", scheme);
+
+            // Shorter than MAC + IV + one block
+            assert!(decrypt(&encrypted[..63], &KEY)
+                .unwrap_err()
+                .starts_with("The encrypted data must be at least"));
+
+            // Truncated or extended data fails authentication
+            let mut extended = encrypted.clone();
+            extended.push(0);
+            assert!(decrypt(&extended, &KEY).is_err(), "{}", scheme);
+
+            // Only 256-bit keys are used, as in the TypeScript packages
+            assert!(encrypt(b"test", &KEY[..16], &IV).is_err(), "{}", scheme);
+            assert!(decrypt(&encrypted, &KEY[..16]).is_err(), "{}", scheme);
+        }
+    }
+
+    #[test]
+    fn test_authenticated_misaligned_ciphertext_is_an_error() {
+        // A correctly MACed ciphertext that is not whole blocks still fails
+        // cleanly when decrypting
+        let mut data = IV.to_vec();
+        data.extend_from_slice(&[0x33; 17]);
+        let mut encrypted = sha256_hmac(&KEY, &data).unwrap();
+        encrypted.extend_from_slice(&data);
+        assert!(acs2_decrypt(&encrypted, &KEY).is_err());
+    }
+}
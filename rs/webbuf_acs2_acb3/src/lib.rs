@@ -0,0 +1 @@
+pub mod acs2_acb3;
@@ -0,0 +1,92 @@
+// AES-CBC throughput
+//
+// `per_block` is the previous implementation, which expanded the key and
+// allocated for every 16-byte block. `aescbc` is the one-shot API, which
+// builds an `AesCbc` per message, and `keyed` reuses one `AesCbc` in place.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use webbuf_aescbc::aes::{aes_decrypt, aes_encrypt};
+use webbuf_aescbc::aescbc::{aescbc_decrypt, aescbc_encrypt, AesCbc};
+
+const SIZES: [usize; 3] = [64, 4096, 1024 * 1024];
+const KEY: [u8; 32] = [7; 32];
+const IV: [u8; 16] = [9; 16];
+
+fn per_block_encrypt(plaintext: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+    let pad_size = 16 - plaintext.len() % 16;
+    let mut padded = plaintext.to_vec();
+    padded.extend(vec![pad_size as u8; pad_size]);
+    let mut ciphertext = vec![];
+    let mut prev_block = iv.to_vec();
+    for block in padded.chunks(16) {
+        let xored: Vec<u8> = block.iter().zip(&prev_block).map(|(a, b)| a ^ b).collect();
+        let encrypted_block = aes_encrypt(key, &xored).unwrap();
+        ciphertext.extend(&encrypted_block);
+        prev_block = encrypted_block;
+    }
+    ciphertext
+}
+
+fn per_block_decrypt(ciphertext: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+    let mut plaintext = vec![];
+    let mut prev_block = iv.to_vec();
+    for block in ciphertext.chunks(16) {
+        let decrypted_block = aes_decrypt(key, block).unwrap();
+        plaintext.extend(decrypted_block.iter().zip(&prev_block).map(|(a, b)| a ^ b));
+        prev_block = block.to_vec();
+    }
+    let pad_size = plaintext[plaintext.len() - 1] as usize;
+    plaintext.truncate(plaintext.len() - pad_size);
+    plaintext
+}
+
+fn bench_encrypt(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt");
+    let cbc = AesCbc::new(&KEY).unwrap();
+    for size in SIZES {
+        let plaintext = vec![0x5a; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("per_block", size), &plaintext, |b, pt| {
+            b.iter(|| per_block_encrypt(black_box(pt), &KEY, &IV))
+        });
+        group.bench_with_input(BenchmarkId::new("aescbc", size), &plaintext, |b, pt| {
+            b.iter(|| aescbc_encrypt(black_box(pt), &KEY, &IV).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("keyed", size), &plaintext, |b, pt| {
+            let mut buf = Vec::with_capacity(size + 16);
+            b.iter(|| {
+                buf.clear();
+                buf.extend_from_slice(pt);
+                cbc.encrypt_in_place(black_box(&mut buf), &IV).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_decrypt(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decrypt");
+    let cbc = AesCbc::new(&KEY).unwrap();
+    for size in SIZES {
+        let ciphertext = cbc.encrypt(&vec![0x5a; size], &IV).unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("per_block", size), &ciphertext, |b, ct| {
+            b.iter(|| per_block_decrypt(black_box(ct), &KEY, &IV))
+        });
+        group.bench_with_input(BenchmarkId::new("aescbc", size), &ciphertext, |b, ct| {
+            b.iter(|| aescbc_decrypt(black_box(ct), &KEY, &IV).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("keyed", size), &ciphertext, |b, ct| {
+            let mut buf = Vec::with_capacity(ct.len());
+            b.iter(|| {
+                buf.clear();
+                buf.extend_from_slice(ct);
+                cbc.decrypt_in_place(black_box(&mut buf), &IV).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_encrypt, bench_decrypt);
+criterion_main!(benches);
@@ -2,83 +2,110 @@ use aes::cipher::generic_array::GenericArray;
 use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
 use aes::{Aes128, Aes192, Aes256}; // Import AES with different key sizes
 
+pub use aes::Block;
+
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AesKeySize {
     Aes128,
     Aes192,
     Aes256,
 }
 
-#[cfg_attr(feature = "wasm", wasm_bindgen)]
-pub fn aes_encrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
-    if key.len() != 16 && key.len() != 24 && key.len() != 32 {
-        return Err("Invalid key size: expected 16, 24, or 32 bytes".to_string());
-    }
-    if data.len() != 16 {
-        return Err("Data length must be 16 bytes".to_string());
-    }
-    match key.len() {
-        16 => {
-            let cipher = Aes128::new(GenericArray::from_slice(key));
-            let mut data = data.to_vec();
-            let block = GenericArray::from_mut_slice(&mut data);
-            cipher.encrypt_block(block);
-            Ok(block.to_vec())
-        }
-        24 => {
-            let cipher = Aes192::new(GenericArray::from_slice(key));
-            let mut data = data.to_vec();
-            let block = GenericArray::from_mut_slice(&mut data);
-            cipher.encrypt_block(block);
-            Ok(block.to_vec())
+/// AES block cipher with an expanded key
+/// Build it once per key and reuse it for every block, instead of calling
+/// `aes_encrypt`/`aes_decrypt`, which expand the key on each call.
+#[derive(Clone)]
+pub struct AesCipher {
+    inner: AesCipherInner,
+}
+
+#[derive(Clone)]
+enum AesCipherInner {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl AesCipher {
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        let inner = match key.len() {
+            16 => AesCipherInner::Aes128(Aes128::new(GenericArray::from_slice(key))),
+            24 => AesCipherInner::Aes192(Aes192::new(GenericArray::from_slice(key))),
+            32 => AesCipherInner::Aes256(Aes256::new(GenericArray::from_slice(key))),
+            _ => return Err("Invalid key size: expected 16, 24, or 32 bytes".to_string()),
+        };
+        Ok(AesCipher { inner })
+    }
+
+    pub fn key_size(&self) -> AesKeySize {
+        match self.inner {
+            AesCipherInner::Aes128(_) => AesKeySize::Aes128,
+            AesCipherInner::Aes192(_) => AesKeySize::Aes192,
+            AesCipherInner::Aes256(_) => AesKeySize::Aes256,
         }
-        32 => {
-            let cipher = Aes256::new(GenericArray::from_slice(key));
-            let mut data = data.to_vec();
-            let block = GenericArray::from_mut_slice(&mut data);
-            cipher.encrypt_block(block);
-            Ok(block.to_vec())
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        let block = GenericArray::from_mut_slice(block);
+        match &self.inner {
+            AesCipherInner::Aes128(cipher) => cipher.encrypt_block(block),
+            AesCipherInner::Aes192(cipher) => cipher.encrypt_block(block),
+            AesCipherInner::Aes256(cipher) => cipher.encrypt_block(block),
         }
-        _ => Err("Invalid key size: expected 16, 24, or 32 bytes".to_string()),
     }
-}
 
-#[cfg_attr(feature = "wasm", wasm_bindgen)]
-pub fn aes_decrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
-    if key.len() != 16 && key.len() != 24 && key.len() != 32 {
-        return Err("Invalid key size: expected 16, 24, or 32 bytes".to_string());
-    }
-    if data.len() != 16 {
-        return Err("Data length must be 16 bytes".to_string());
-    }
-    match key.len() {
-        16 => {
-            let cipher = Aes128::new(GenericArray::from_slice(key));
-            let mut data = data.to_vec();
-            let block = GenericArray::from_mut_slice(&mut data);
-            cipher.decrypt_block(block);
-            Ok(block.to_vec())
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        let block = GenericArray::from_mut_slice(block);
+        match &self.inner {
+            AesCipherInner::Aes128(cipher) => cipher.decrypt_block(block),
+            AesCipherInner::Aes192(cipher) => cipher.decrypt_block(block),
+            AesCipherInner::Aes256(cipher) => cipher.decrypt_block(block),
         }
-        24 => {
-            let cipher = Aes192::new(GenericArray::from_slice(key));
-            let mut data = data.to_vec();
-            let block = GenericArray::from_mut_slice(&mut data);
-            cipher.decrypt_block(block);
-            Ok(block.to_vec())
+    }
+
+    /// Encrypt independent blocks, several at a time where the CPU supports it
+    pub fn encrypt_blocks(&self, blocks: &mut [Block]) {
+        match &self.inner {
+            AesCipherInner::Aes128(cipher) => cipher.encrypt_blocks(blocks),
+            AesCipherInner::Aes192(cipher) => cipher.encrypt_blocks(blocks),
+            AesCipherInner::Aes256(cipher) => cipher.encrypt_blocks(blocks),
         }
-        32 => {
-            let cipher = Aes256::new(GenericArray::from_slice(key));
-            let mut data = data.to_vec();
-            let block = GenericArray::from_mut_slice(&mut data);
-            cipher.decrypt_block(block);
-            Ok(block.to_vec())
+    }
+
+    /// Decrypt independent blocks, several at a time where the CPU supports it
+    pub fn decrypt_blocks(&self, blocks: &mut [Block]) {
+        match &self.inner {
+            AesCipherInner::Aes128(cipher) => cipher.decrypt_blocks(blocks),
+            AesCipherInner::Aes192(cipher) => cipher.decrypt_blocks(blocks),
+            AesCipherInner::Aes256(cipher) => cipher.decrypt_blocks(blocks),
         }
-        _ => Err("Invalid key size: expected 16, 24, or 32 bytes".to_string()),
     }
 }
 
+fn to_block(data: &[u8]) -> Result<[u8; 16], String> {
+    data.try_into()
+        .map_err(|_| "Data length must be 16 bytes".to_string())
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aes_encrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = AesCipher::new(key)?;
+    let mut block = to_block(data)?;
+    cipher.encrypt_block(&mut block);
+    Ok(block.to_vec())
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aes_decrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = AesCipher::new(key)?;
+    let mut block = to_block(data)?;
+    cipher.decrypt_block(&mut block);
+    Ok(block.to_vec())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +225,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cipher_matches_single_block_functions() {
+        for key_len in [16, 24, 32] {
+            let key: Vec<u8> = (0..key_len as u8).collect();
+            let cipher = AesCipher::new(&key).unwrap();
+            let mut blocks: Vec<Block> = (0..9u8).map(|i| [i; 16].into()).collect();
+            let expected: Vec<Vec<u8>> = blocks
+                .iter()
+                .map(|block| aes_encrypt(&key, block).unwrap())
+                .collect();
+
+            cipher.encrypt_blocks(&mut blocks);
+            for (block, expected) in blocks.iter().zip(&expected) {
+                assert_eq!(block.as_slice(), expected.as_slice());
+            }
+
+            let mut block: [u8; 16] = blocks[3].into();
+            cipher.decrypt_block(&mut block);
+            assert_eq!(block, [3; 16]);
+
+            cipher.decrypt_blocks(&mut blocks);
+            for (i, block) in blocks.iter().enumerate() {
+                assert_eq!(block.as_slice(), [i as u8; 16]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cipher_key_size() {
+        assert_eq!(
+            AesCipher::new(&[0; 16]).unwrap().key_size(),
+            AesKeySize::Aes128
+        );
+        assert_eq!(
+            AesCipher::new(&[0; 24]).unwrap().key_size(),
+            AesKeySize::Aes192
+        );
+        assert_eq!(
+            AesCipher::new(&[0; 32]).unwrap().key_size(),
+            AesKeySize::Aes256
+        );
+        assert!(AesCipher::new(&[0; 20]).is_err());
+    }
+
     use serde::Deserialize;
     use std::fs;
     use std::path::Path;
@@ -1,272 +1,561 @@
-use crate::aes::{aes_decrypt, aes_encrypt};
+use crate::aes::{AesCipher, Block};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater};
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-// Helper function to XOR two byte slices
-fn xor_bufs(buf1: &[u8], buf2: &[u8]) -> Vec<u8> {
-    if buf1.len() != buf2.len() {
-        panic!("Buffers must be the same length");
-    }
-    buf1.iter()
-        .zip(buf2.iter())
-        .map(|(&x1, &x2)| x1 ^ x2)
-        .collect()
+const BLOCK_SIZE: usize = 16;
+
+// Blocks handed to the cipher at once when decrypting. The aes crate
+// processes up to eight blocks in parallel with AES-NI.
+const PAR_BLOCKS: usize = 8;
+
+fn to_iv(iv: &[u8]) -> Result<[u8; BLOCK_SIZE], String> {
+    iv.try_into().map_err(|_| "Invalid IV size".to_string())
 }
 
-// PKCS#7 Padding
-fn pkcs7_pad(buf: &[u8], block_size: usize) -> Vec<u8> {
-    if block_size == 0 {
-        panic!("Invalid block size");
+// PKCS#7 Padding, always adding between 1 and 16 bytes
+fn pkcs7_pad(buf: &mut Vec<u8>) {
+    let pad_size = BLOCK_SIZE - buf.len() % BLOCK_SIZE;
+    buf.resize(buf.len() + pad_size, pad_size as u8);
+}
+
+// PKCS#7 Unpadding, returning the number of padding bytes in the last block
+//
+// The padding bytes are checked in constant time and every bad padding gives
+// the same error, so failures do not act as a padding oracle. Only the block
+// length, which is public, is checked with a branch.
+fn pkcs7_padding_len(last_block: &[u8]) -> Result<usize, String> {
+    if last_block.is_empty() || last_block.len() > BLOCK_SIZE {
+        return Err("Invalid padding".to_string());
     }
-    if buf.len() >= block_size {
-        panic!("Buffer is already a full block");
+
+    let len = last_block.len() as u8;
+    let pad_size = last_block[last_block.len() - 1];
+    let mut valid = !pad_size.ct_eq(&0) & !pad_size.ct_gt(&len);
+
+    // Every byte within pad_size of the end must equal pad_size
+    for (i, &byte) in last_block.iter().enumerate() {
+        let dist_from_end = len - i as u8;
+        let in_padding = !dist_from_end.ct_gt(&pad_size);
+        valid &= !in_padding | byte.ct_eq(&pad_size);
     }
-    let pad_size = block_size - (buf.len() % block_size);
-    let mut padded_buf = buf.to_vec();
-    padded_buf.extend(vec![pad_size as u8; pad_size]);
-    padded_buf
-}
 
-// PKCS#7 Unpadding
-fn pkcs7_unpad(padded_buf: &[u8]) -> Vec<u8> {
-    if padded_buf.is_empty() {
-        panic!("Empty buffer");
+    if !bool::from(valid) {
+        return Err("Invalid padding".to_string());
     }
-    if padded_buf.len() > 16 {
-        panic!("Invalid padding");
+    Ok(pad_size as usize)
+}
+
+// ISO/IEC 7816-4 Padding: 0x80 followed by zero bytes, always adding between
+// 1 and 16 bytes
+fn iso7816_pad(buf: &mut Vec<u8>) {
+    let pad_size = BLOCK_SIZE - buf.len() % BLOCK_SIZE;
+    buf.push(0x80);
+    buf.resize(buf.len() + pad_size - 1, 0);
+}
+
+// ISO/IEC 7816-4 Unpadding, returning the number of padding bytes in the last
+// block
+//
+// Every byte is examined whatever the position of the 0x80 marker, and every
+// bad padding gives the same error, as for PKCS#7.
+fn iso7816_padding_len(last_block: &[u8]) -> Result<usize, String> {
+    if last_block.is_empty() || last_block.len() > BLOCK_SIZE {
+        return Err("Invalid padding".to_string());
     }
 
-    let pad_size = *padded_buf.last().unwrap() as usize;
-    if pad_size == 0 || pad_size > padded_buf.len() {
-        panic!("Invalid padding");
+    let mut found = Choice::from(0);
+    let mut valid = Choice::from(1);
+    let mut pad_size = 0u8;
+    for (i, &byte) in last_block.iter().enumerate().rev() {
+        let is_marker = byte.ct_eq(&0x80);
+        // Only zeros may follow the marker
+        valid &= found | is_marker | byte.ct_eq(&0);
+        pad_size.conditional_assign(&((last_block.len() - i) as u8), !found & is_marker);
+        found |= is_marker;
     }
 
-    // Check if all padding bytes match the pad_size value
-    let padding = &padded_buf[padded_buf.len() - pad_size..];
-    if !padding.iter().all(|&byte| byte as usize == pad_size) {
-        panic!("Invalid padding");
+    if !bool::from(valid & found) {
+        return Err("Invalid padding".to_string());
     }
+    Ok(pad_size as usize)
+}
 
-    // Return buffer without the padding bytes
-    padded_buf[..padded_buf.len() - pad_size].to_vec()
+/// Padding used by AES-CBC
+/// The ciphertext stealing modes (NIST SP 800-38A Addendum) produce a
+/// ciphertext the same length as the plaintext, which must be at least 16
+/// bytes. They differ only in the order of the last two blocks.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CbcPadding {
+    /// PKCS#7, as used by `aescbc_encrypt`
+    Pkcs7,
+    /// No padding: the plaintext must be a multiple of 16 bytes
+    None,
+    /// ISO/IEC 7816-4: 0x80 followed by zero bytes
+    Iso7816,
+    /// CBC-CS1: the partial block stays second-to-last (no reordering)
+    Cs1,
+    /// CBC-CS2: as CS1 when block-aligned, otherwise as CS3
+    Cs2,
+    /// CBC-CS3: the last two blocks are always swapped, as in Kerberos
+    Cs3,
 }
 
-// Splits a buffer into blocks of a specified size, padding if necessary
-fn buf_to_blocks(buf: &[u8], block_size: usize) -> Vec<Vec<u8>> {
-    let mut blocks = vec![];
-    let mut i = 0;
-    while i <= buf.len() {
-        let end = std::cmp::min(i + block_size, buf.len());
-        let mut block = buf[i..end].to_vec();
-        if block.len() < block_size {
-            block = pkcs7_pad(&block, block_size);
+impl CbcPadding {
+    fn is_stealing(self) -> bool {
+        matches!(self, CbcPadding::Cs1 | CbcPadding::Cs2 | CbcPadding::Cs3)
+    }
+
+    // Whether the last full block comes after the shortened second-to-last
+    // block, given the length of the last block
+    fn swaps_last_blocks(self, last_len: usize) -> bool {
+        match self {
+            CbcPadding::Cs2 => last_len != BLOCK_SIZE,
+            CbcPadding::Cs3 => true,
+            _ => false,
         }
-        blocks.push(block);
-        i += block_size;
     }
-    blocks
 }
 
-// Combines blocks into a single buffer, removing padding from the last block
-fn blocks_to_buf(blocks: Vec<Vec<u8>>) -> Vec<u8> {
-    let mut buf = vec![];
-    for (i, block) in blocks.iter().enumerate() {
-        if i == blocks.len() - 1 {
-            buf.extend(pkcs7_unpad(block));
+/// AES-CBC with an expanded key
+/// Build one per key and reuse it for every message under that key.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone)]
+pub struct AesCbc {
+    cipher: AesCipher,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl AesCbc {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(aes_key: &[u8]) -> Result<AesCbc, String> {
+        Ok(AesCbc {
+            cipher: AesCipher::new(aes_key)?,
+        })
+    }
+
+    /// Encrypt with PKCS#7 padding
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = encrypt_pkcs7))]
+    pub fn encrypt(&self, plaintext: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+        self.encrypt_padded(plaintext, iv, CbcPadding::Pkcs7)
+    }
+
+    /// Decrypt and remove PKCS#7 padding
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = decrypt_pkcs7))]
+    pub fn decrypt(&self, ciphertext: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+        self.decrypt_padded(ciphertext, iv, CbcPadding::Pkcs7)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = encrypt_with_padding))]
+    pub fn encrypt_padded(
+        &self,
+        plaintext: &[u8],
+        iv: &[u8],
+        padding: CbcPadding,
+    ) -> Result<Vec<u8>, String> {
+        let mut buf = Vec::with_capacity(plaintext.len() / BLOCK_SIZE * BLOCK_SIZE + BLOCK_SIZE);
+        buf.extend_from_slice(plaintext);
+        self.encrypt_in_place_padded(&mut buf, iv, padding)?;
+        Ok(buf)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = decrypt_with_padding))]
+    pub fn decrypt_padded(
+        &self,
+        ciphertext: &[u8],
+        iv: &[u8],
+        padding: CbcPadding,
+    ) -> Result<Vec<u8>, String> {
+        let mut buf = ciphertext.to_vec();
+        self.decrypt_in_place_padded(&mut buf, iv, padding)?;
+        Ok(buf)
+    }
+}
+
+impl AesCbc {
+    /// Pad `buf` with PKCS#7 and encrypt it in place
+    pub fn encrypt_in_place(&self, buf: &mut Vec<u8>, iv: &[u8]) -> Result<(), String> {
+        self.encrypt_in_place_padded(buf, iv, CbcPadding::Pkcs7)
+    }
+
+    /// Decrypt `buf` in place and remove the PKCS#7 padding
+    /// On error the contents of `buf` are unspecified.
+    pub fn decrypt_in_place(&self, buf: &mut Vec<u8>, iv: &[u8]) -> Result<(), String> {
+        self.decrypt_in_place_padded(buf, iv, CbcPadding::Pkcs7)
+    }
+
+    /// Pad `buf` and encrypt it in place
+    /// On error `buf` is left unchanged.
+    pub fn encrypt_in_place_padded(
+        &self,
+        buf: &mut Vec<u8>,
+        iv: &[u8],
+        padding: CbcPadding,
+    ) -> Result<(), String> {
+        let iv = to_iv(iv)?;
+        match padding {
+            CbcPadding::Pkcs7 => pkcs7_pad(buf),
+            CbcPadding::Iso7816 => iso7816_pad(buf),
+            CbcPadding::None => {
+                if !buf.len().is_multiple_of(BLOCK_SIZE) {
+                    return Err(
+                        "Invalid plaintext length: expected a multiple of 16 bytes".to_string()
+                    );
+                }
+            }
+            CbcPadding::Cs1 | CbcPadding::Cs2 | CbcPadding::Cs3 => {
+                return self.encrypt_stealing(buf, iv, padding);
+            }
+        }
+        self.encrypt_blocks(buf, iv);
+        Ok(())
+    }
+
+    /// Decrypt `buf` in place and remove the padding
+    /// On error the contents of `buf` are unspecified.
+    pub fn decrypt_in_place_padded(
+        &self,
+        buf: &mut Vec<u8>,
+        iv: &[u8],
+        padding: CbcPadding,
+    ) -> Result<(), String> {
+        let iv = to_iv(iv)?;
+        if padding.is_stealing() {
+            return self.decrypt_stealing(buf, iv, padding);
+        }
+        if padding == CbcPadding::None {
+            if !buf.len().is_multiple_of(BLOCK_SIZE) {
+                return Err(
+                    "Invalid ciphertext length: expected a multiple of 16 bytes".to_string()
+                );
+            }
+            self.decrypt_blocks(buf, iv);
+            return Ok(());
+        }
+
+        // Padding always adds at least one byte, so there is at least one block
+        if buf.is_empty() || !buf.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(
+                "Invalid ciphertext length: expected a non-zero multiple of 16 bytes".to_string(),
+            );
+        }
+        self.decrypt_blocks(buf, iv);
+
+        let last_block = &buf[buf.len() - BLOCK_SIZE..];
+        let pad_size = if padding == CbcPadding::Pkcs7 {
+            pkcs7_padding_len(last_block)?
         } else {
-            buf.extend(block);
+            iso7816_padding_len(last_block)?
+        };
+        buf.truncate(buf.len() - pad_size);
+        Ok(())
+    }
+
+    // CBC over whole blocks
+    fn encrypt_blocks(&self, buf: &mut [u8], mut prev_block: [u8; BLOCK_SIZE]) {
+        for chunk in buf.chunks_exact_mut(BLOCK_SIZE) {
+            let block: &mut [u8; BLOCK_SIZE] =
+                chunk.try_into().expect("chunks_exact yields whole blocks");
+            for (byte, prev) in block.iter_mut().zip(&prev_block) {
+                *byte ^= prev;
+            }
+            self.cipher.encrypt_block(block);
+            prev_block = *block;
         }
     }
-    buf
-}
 
-// AES-CBC Encrypt
-#[cfg_attr(feature = "wasm", wasm_bindgen)]
-pub fn aescbc_encrypt(plaintext: &[u8], aes_key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
-    let block_size = 16;
+    fn decrypt_blocks(&self, buf: &mut [u8], mut prev_block: [u8; BLOCK_SIZE]) {
+        // Unlike encryption, every block can be decrypted independently
+        let mut blocks = [Block::default(); PAR_BLOCKS];
+        for chunk in buf.chunks_mut(PAR_BLOCKS * BLOCK_SIZE) {
+            let n = chunk.len() / BLOCK_SIZE;
+            for (block, ciphertext) in blocks.iter_mut().zip(chunk.chunks_exact(BLOCK_SIZE)) {
+                block.copy_from_slice(ciphertext);
+            }
+            self.cipher.decrypt_blocks(&mut blocks[..n]);
+
+            // XOR with the previous ciphertext block, back to front so each
+            // ciphertext block is read before it is overwritten
+            let next_prev_block: [u8; BLOCK_SIZE] = chunk[chunk.len() - BLOCK_SIZE..]
+                .try_into()
+                .expect("chunk ends with a whole block");
+            for i in (0..n).rev() {
+                let (before, rest) = chunk.split_at_mut(i * BLOCK_SIZE);
+                let prev = if i == 0 {
+                    &prev_block[..]
+                } else {
+                    &before[(i - 1) * BLOCK_SIZE..]
+                };
+                for ((byte, decrypted), prev) in rest.iter_mut().zip(&blocks[i]).zip(prev) {
+                    *byte = decrypted ^ prev;
+                }
+            }
+            prev_block = next_prev_block;
+        }
+    }
 
-    if iv.len() != block_size || ![16, 24, 32].contains(&aes_key.len()) {
-        return Err("Invalid IV or key size".to_string());
+    // Ciphertext stealing: CBC over the zero-padded plaintext, keeping only as
+    // much of the second-to-last ciphertext block as the last plaintext block
+    // is long
+    fn encrypt_stealing(
+        &self,
+        buf: &mut Vec<u8>,
+        iv: [u8; BLOCK_SIZE],
+        padding: CbcPadding,
+    ) -> Result<(), String> {
+        if buf.len() < BLOCK_SIZE {
+            return Err("Invalid plaintext length: expected at least 16 bytes".to_string());
+        }
+        let last_len = (buf.len() - 1) % BLOCK_SIZE + 1;
+        buf.resize(buf.len().next_multiple_of(BLOCK_SIZE), 0);
+        self.encrypt_blocks(buf, iv);
+        if buf.len() == BLOCK_SIZE {
+            return Ok(());
+        }
+
+        let second_last = buf.len() - 2 * BLOCK_SIZE;
+        buf.drain(second_last + last_len..second_last + BLOCK_SIZE);
+        if padding.swaps_last_blocks(last_len) {
+            buf[second_last..].rotate_left(last_len);
+        }
+        Ok(())
     }
 
-    let blocks = buf_to_blocks(plaintext, block_size);
-    let mut ciphertext = vec![];
-    let mut prev_block = iv.to_vec();
+    fn decrypt_stealing(
+        &self,
+        buf: &mut Vec<u8>,
+        iv: [u8; BLOCK_SIZE],
+        padding: CbcPadding,
+    ) -> Result<(), String> {
+        if buf.len() < BLOCK_SIZE {
+            return Err("Invalid ciphertext length: expected at least 16 bytes".to_string());
+        }
+        if buf.len() == BLOCK_SIZE {
+            self.decrypt_blocks(buf, iv);
+            return Ok(());
+        }
+
+        let last_len = (buf.len() - 1) % BLOCK_SIZE + 1;
+        let second_last = buf.len() - BLOCK_SIZE - last_len;
+        if padding.swaps_last_blocks(last_len) {
+            buf[second_last..].rotate_right(last_len);
+        }
+        if last_len == BLOCK_SIZE {
+            self.decrypt_blocks(buf, iv);
+            return Ok(());
+        }
+
+        // Decrypting the last block gives the second-to-last ciphertext block
+        // XORed with the zero-padded last plaintext block, which recovers
+        // both the last plaintext and the stolen ciphertext bytes
+        let mut decrypted: [u8; BLOCK_SIZE] = buf[buf.len() - BLOCK_SIZE..]
+            .try_into()
+            .expect("buffer ends with a whole block");
+        self.cipher.decrypt_block(&mut decrypted);
+        let mut last_plaintext = [0u8; BLOCK_SIZE];
+        for (byte, (decrypted, ciphertext)) in last_plaintext.iter_mut().zip(
+            decrypted
+                .iter()
+                .zip(&buf[second_last..second_last + last_len]),
+        ) {
+            *byte = decrypted ^ ciphertext;
+        }
 
-    for block in blocks {
-        let xored = xor_bufs(&block, &prev_block);
-        let encrypted_block = aes_encrypt(aes_key, &xored)?;
-        ciphertext.extend(&encrypted_block);
-        prev_block = encrypted_block;
+        buf.truncate(second_last + last_len);
+        buf.extend_from_slice(&decrypted[last_len..]);
+        self.decrypt_blocks(buf, iv);
+        buf.extend_from_slice(&last_plaintext[..last_len]);
+        Ok(())
     }
+}
 
-    Ok(ciphertext)
+// AES-CBC Encrypt
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aescbc_encrypt(plaintext: &[u8], aes_key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+    if iv.len() != BLOCK_SIZE || ![16, 24, 32].contains(&aes_key.len()) {
+        return Err("Invalid IV or key size".to_string());
+    }
+    AesCbc::new(aes_key)?.encrypt(plaintext, iv)
 }
 
 // AES-CBC Decrypt
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn aescbc_decrypt(ciphertext: &[u8], aes_key: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
-    let block_size = 16;
-
-    if iv.len() != block_size {
+    if iv.len() != BLOCK_SIZE {
         return Err("Invalid IV size".to_string());
     }
+    AesCbc::new(aes_key)?.decrypt(ciphertext, iv)
+}
 
-    let ciphertext_blocks = ciphertext
-        .chunks(block_size)
-        .map(|chunk| chunk.to_vec())
-        .collect::<Vec<_>>();
-
-    let mut plaintext_blocks = vec![];
-    let mut prev_block = iv.to_vec();
-
-    for block in ciphertext_blocks {
-        let decrypted_block = aes_decrypt(aes_key, &block)?;
-        let plaintext_block = xor_bufs(&decrypted_block, &prev_block);
-        plaintext_blocks.push(plaintext_block);
-        prev_block = block;
-    }
+// AES-CBC Encrypt with a choice of padding
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aescbc_encrypt_padded(
+    plaintext: &[u8],
+    aes_key: &[u8],
+    iv: &[u8],
+    padding: CbcPadding,
+) -> Result<Vec<u8>, String> {
+    AesCbc::new(aes_key)?.encrypt_padded(plaintext, iv, padding)
+}
 
-    Ok(blocks_to_buf(plaintext_blocks))
+// AES-CBC Decrypt with a choice of padding
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aescbc_decrypt_padded(
+    ciphertext: &[u8],
+    aes_key: &[u8],
+    iv: &[u8],
+    padding: CbcPadding,
+) -> Result<Vec<u8>, String> {
+    AesCbc::new(aes_key)?.decrypt_padded(ciphertext, iv, padding)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aes::aes_encrypt;
     use serde::Deserialize;
     use std::fs;
     use std::path::Path;
 
-    #[test]
-    fn test_xor_bufs() {
-        // Basic test case
-        let buf1 = [0b10101010, 0b11110000];
-        let buf2 = [0b01010101, 0b00001111];
-        let result = xor_bufs(&buf1, &buf2);
-        assert_eq!(result, vec![0b11111111, 0b11111111]);
-
-        // All zeros
-        let buf1 = [0u8; 4];
-        let buf2 = [0u8; 4];
-        let result = xor_bufs(&buf1, &buf2);
-        assert_eq!(result, vec![0, 0, 0, 0]);
-
-        // All ones
-        let buf1 = [0xFFu8; 4];
-        let buf2 = [0xFFu8; 4];
-        let result = xor_bufs(&buf1, &buf2);
-        assert_eq!(result, vec![0, 0, 0, 0]);
-    }
-
     #[test]
     fn test_pkcs7_pad() {
         // Basic test case with 16-byte block size
-        let buf = vec![1, 2, 3, 4, 5, 6, 7, 8];
-        let padded = pkcs7_pad(&buf, 16);
-        assert_eq!(padded, vec![1, 2, 3, 4, 5, 6, 7, 8, 8, 8, 8, 8, 8, 8, 8, 8]);
-
-        // Full block padding of empty block
-        let buf = vec![];
-        let padded = pkcs7_pad(&buf, 16);
-        assert_eq!(
-            padded,
-            vec![16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16]
-        );
-
-        // Edge case: Empty buffer
-        let buf: Vec<u8> = vec![];
-        let padded = pkcs7_pad(&buf, 16);
-        assert_eq!(padded, vec![16; 16]);
-    }
-
-    #[test]
-    fn test_pkcs7_unpad() {
-        // Basic unpadding
-        let padded_buf = vec![1, 2, 3, 4, 5, 6, 7, 8, 8, 8, 8, 8, 8, 8, 8, 8];
-        let unpadded = pkcs7_unpad(&padded_buf);
-        assert_eq!(unpadded, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let mut buf = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        pkcs7_pad(&mut buf);
+        assert_eq!(buf, vec![1, 2, 3, 4, 5, 6, 7, 8, 8, 8, 8, 8, 8, 8, 8, 8]);
 
-        // Full block padding of empty block
-        let padded_buf = vec![
-            16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16,
-        ];
-        let unpadded = pkcs7_unpad(&padded_buf);
-        let empty_vec: Vec<u8> = vec![];
-        assert_eq!(unpadded, empty_vec);
+        // Edge case: Empty buffer gets a full block of padding
+        let mut buf: Vec<u8> = vec![];
+        pkcs7_pad(&mut buf);
+        assert_eq!(buf, vec![16; 16]);
     }
 
     #[test]
-    fn test_buf_to_blocks_exact_block_size() {
-        let buf = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
-        let block_size = 16;
-        let blocks = buf_to_blocks(&buf, block_size);
+    fn test_pkcs7_pad_exact_block_size() {
+        let mut buf = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+        pkcs7_pad(&mut buf);
         assert_eq!(
-            blocks,
+            buf,
             vec![
-                vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
-                vec![16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16]
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 16, 16, 16, 16, 16, 16, 16,
+                16, 16, 16, 16, 16, 16, 16, 16, 16
             ]
         );
     }
 
     #[test]
-    fn test_buf_to_blocks_with_padding() {
-        let buf = vec![1, 2, 3, 4, 5, 6, 7, 8];
-        let block_size = 16;
-        let blocks = buf_to_blocks(&buf, block_size);
-        assert_eq!(
-            blocks,
-            vec![vec![1, 2, 3, 4, 5, 6, 7, 8, 8, 8, 8, 8, 8, 8, 8, 8]]
-        );
-    }
-
-    #[test]
-    fn test_buf_to_blocks_multiple_blocks() {
-        let buf = vec![
+    fn test_pkcs7_pad_multiple_blocks() {
+        let mut buf = vec![
             1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
         ];
-        let block_size = 16;
-        let blocks = buf_to_blocks(&buf, block_size);
+        pkcs7_pad(&mut buf);
         assert_eq!(
-            blocks,
+            buf,
             vec![
-                vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
-                vec![17, 18, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14]
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 14, 14, 14, 14, 14,
+                14, 14, 14, 14, 14, 14, 14, 14, 14
             ]
         );
     }
 
-    // this panics - as it should
-    // #[test]
-    // fn test_blocks_to_buf_no_padding() {
-    //     let blocks = vec![
-    //         vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
-    //     ];
-    //     let buf = blocks_to_buf(blocks);
-    //     assert_eq!(buf, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
-    // }
+    #[test]
+    fn test_pkcs7_padding_len() {
+        // Basic unpadding
+        let padded = [1, 2, 3, 4, 5, 6, 7, 8, 8, 8, 8, 8, 8, 8, 8, 8];
+        assert_eq!(pkcs7_padding_len(&padded).unwrap(), 8);
+
+        // Full block padding of empty block
+        assert_eq!(pkcs7_padding_len(&[16; 16]).unwrap(), 16);
+
+        // Single byte padding
+        let padded = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 1];
+        assert_eq!(pkcs7_padding_len(&padded).unwrap(), 1);
+    }
 
     #[test]
-    fn test_blocks_to_buf_with_unpadding() {
-        let blocks = vec![
+    fn test_pkcs7_padding_len_invalid_padding() {
+        let invalid: Vec<Vec<u8>> = vec![
+            // Empty buffer
+            vec![],
+            // Longer than a block
+            vec![1; 17],
+            // Zero pad byte
+            vec![0; 16],
+            // Pad byte larger than the block
+            vec![17; 16],
+            // Last byte of unpadded data
             vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
-            vec![
-                17, 18, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14,
-            ],
+            // Padding bytes that disagree with the pad length
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 4, 4, 3, 4],
+            // Pad byte larger than a short buffer
+            vec![5, 5, 5, 5],
         ];
-        let buf = blocks_to_buf(blocks);
-        assert_eq!(
-            buf,
-            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
-        );
+        for padded in invalid {
+            assert_eq!(
+                pkcs7_padding_len(&padded).unwrap_err(),
+                "Invalid padding",
+                "{:?}",
+                padded
+            );
+        }
+    }
+
+    // CBC built from single-block aes_encrypt/aes_decrypt calls
+    fn reference_encrypt(plaintext: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+        let mut padded = plaintext.to_vec();
+        pkcs7_pad(&mut padded);
+        let mut prev = iv.to_vec();
+        let mut ciphertext = vec![];
+        for block in padded.chunks(16) {
+            let xored: Vec<u8> = block.iter().zip(&prev).map(|(a, b)| a ^ b).collect();
+            prev = aes_encrypt(key, &xored).unwrap();
+            ciphertext.extend(&prev);
+        }
+        ciphertext
+    }
+
+    #[test]
+    fn test_aescbc_matches_single_block_reference() {
+        // Lengths on both sides of the eight-block decryption batches
+        let key: Vec<u8> = (0..32).collect();
+        let iv = [7u8; 16];
+        let cbc = AesCbc::new(&key).unwrap();
+        for len in [0, 1, 15, 16, 17, 127, 128, 129, 255, 256, 1000] {
+            let plaintext: Vec<u8> = (0..len).map(|i| (i * 13) as u8).collect();
+            let ciphertext = cbc.encrypt(&plaintext, &iv).unwrap();
+            assert_eq!(
+                ciphertext,
+                reference_encrypt(&plaintext, &key, &iv),
+                "len {}",
+                len
+            );
+            assert_eq!(
+                cbc.decrypt(&ciphertext, &iv).unwrap(),
+                plaintext,
+                "len {}",
+                len
+            );
+        }
     }
 
     #[test]
-    fn test_blocks_to_buf_with_single_byte_padding() {
-        let blocks = vec![vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 1]];
-        let buf = blocks_to_buf(blocks);
-        assert_eq!(buf, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    fn test_aescbc_in_place() {
+        let cbc = AesCbc::new(&AES_KEY_192).unwrap();
+        let plaintext = b"encrypted and decrypted in the same buffer".to_vec();
+
+        let mut buf = plaintext.clone();
+        cbc.encrypt_in_place(&mut buf, &IV).unwrap();
+        assert_eq!(buf, aescbc_encrypt(&plaintext, &AES_KEY_192, &IV).unwrap());
+
+        cbc.decrypt_in_place(&mut buf, &IV).unwrap();
+        assert_eq!(buf, plaintext);
+
+        // Invalid IVs leave the buffer untouched
+        assert!(cbc.encrypt_in_place(&mut buf, &[0; 8]).is_err());
+        assert!(cbc.decrypt_in_place(&mut buf, &[0; 8]).is_err());
+        assert_eq!(buf, plaintext);
     }
 
     // Sample AES key and IV for tests
@@ -380,6 +669,63 @@ mod tests {
         assert_eq!(decrypted_text, plaintext);
     }
 
+    #[test]
+    fn test_decrypt_truncated_ciphertext() {
+        let plaintext = b"Plaintext with length not multiple of block size";
+        let ciphertext = aescbc_encrypt(plaintext, &AES_KEY_128, &IV).expect("Encryption failed");
+
+        // Dropping whole blocks leaves garbage padding in the new last block
+        let truncated = &ciphertext[..ciphertext.len() - 16];
+        assert_eq!(
+            aescbc_decrypt(truncated, &AES_KEY_128, &IV).unwrap_err(),
+            "Invalid padding"
+        );
+
+        // No ciphertext at all
+        assert!(aescbc_decrypt(&[], &AES_KEY_128, &IV).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_misaligned_ciphertext() {
+        let ciphertext =
+            aescbc_encrypt(b"misaligned", &AES_KEY_128, &IV).expect("Encryption failed");
+        for len in [1, 15, 17, 31] {
+            let mut misaligned = ciphertext.clone();
+            misaligned.resize(len, 0);
+            assert_eq!(
+                aescbc_decrypt(&misaligned, &AES_KEY_128, &IV).unwrap_err(),
+                "Invalid ciphertext length: expected a non-zero multiple of 16 bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn test_decrypt_tampered_padding() {
+        // 24 bytes of plaintext, so the last block ends in eight 0x08 bytes
+        let plaintext = b"twenty-four byte message";
+        let ciphertext = aescbc_encrypt(plaintext, &AES_KEY_256, &IV).expect("Encryption failed");
+
+        // Flipping a bit of the previous ciphertext block flips the same bit
+        // of the decrypted padding
+        for pos in [31 - 16, 24 - 16] {
+            let mut tampered = ciphertext.clone();
+            tampered[pos] ^= 0x01;
+            assert_eq!(
+                aescbc_decrypt(&tampered, &AES_KEY_256, &IV).unwrap_err(),
+                "Invalid padding"
+            );
+        }
+
+        // For a single block, the IV plays the role of the previous block
+        let ciphertext = aescbc_encrypt(b"short", &AES_KEY_256, &IV).expect("Encryption failed");
+        let mut tampered_iv = IV;
+        tampered_iv[15] ^= 0x01;
+        assert_eq!(
+            aescbc_decrypt(&ciphertext, &AES_KEY_256, &tampered_iv).unwrap_err(),
+            "Invalid padding"
+        );
+    }
+
     // Struct for test vectors
     #[derive(Deserialize)]
     struct TestVector {
@@ -431,4 +777,202 @@ mod tests {
             );
         }
     }
+
+    #[derive(Deserialize)]
+    struct PaddingTestVector {
+        name: String,
+        padding: String,
+        key: String,
+        iv: String,
+        pt: String,
+        ct: String,
+    }
+
+    fn parse_padding(name: &str) -> CbcPadding {
+        match name {
+            "none" => CbcPadding::None,
+            "iso7816" => CbcPadding::Iso7816,
+            "cs1" => CbcPadding::Cs1,
+            "cs2" => CbcPadding::Cs2,
+            "cs3" => CbcPadding::Cs3,
+            _ => panic!("Unknown padding {}", name),
+        }
+    }
+
+    #[test]
+    fn test_padding_modes_with_vectors() {
+        let data = fs::read_to_string("vectors/vectors-cbc-padding.json")
+            .expect("Unable to read test vectors file");
+        let vectors: Vec<PaddingTestVector> =
+            serde_json::from_str(&data).expect("Error parsing JSON test vectors");
+
+        for vector in vectors {
+            let padding = parse_padding(&vector.padding);
+            let key = hex_to_bytes(&vector.key);
+            let iv = hex_to_bytes(&vector.iv);
+            let plaintext = hex_to_bytes(&vector.pt);
+            let ciphertext = hex_to_bytes(&vector.ct);
+
+            assert_eq!(
+                aescbc_encrypt_padded(&plaintext, &key, &iv, padding).unwrap(),
+                ciphertext,
+                "{}: {}",
+                vector.padding,
+                vector.name
+            );
+            assert_eq!(
+                aescbc_decrypt_padded(&ciphertext, &key, &iv, padding).unwrap(),
+                plaintext,
+                "{}: {}",
+                vector.padding,
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_padding_modes_round_trip() {
+        let cbc = AesCbc::new(&AES_KEY_256).unwrap();
+        let iv = [5u8; 16];
+        let modes = [
+            CbcPadding::Pkcs7,
+            CbcPadding::None,
+            CbcPadding::Iso7816,
+            CbcPadding::Cs1,
+            CbcPadding::Cs2,
+            CbcPadding::Cs3,
+        ];
+        for padding in modes {
+            for len in 0..=200 {
+                let plaintext: Vec<u8> = (0..len).map(|i| (i * 31) as u8).collect();
+                let Ok(ciphertext) = cbc.encrypt_padded(&plaintext, &iv, padding) else {
+                    continue;
+                };
+                if padding.is_stealing() || padding == CbcPadding::None {
+                    assert_eq!(ciphertext.len(), len, "{:?} length {}", padding, len);
+                }
+                let mut buf = plaintext.clone();
+                cbc.encrypt_in_place_padded(&mut buf, &iv, padding).unwrap();
+                assert_eq!(buf, ciphertext, "{:?} length {}", padding, len);
+                cbc.decrypt_in_place_padded(&mut buf, &iv, padding).unwrap();
+                assert_eq!(buf, plaintext, "{:?} length {}", padding, len);
+            }
+        }
+
+        // PKCS#7 is the default
+        assert_eq!(
+            cbc.encrypt_padded(b"default", &IV, CbcPadding::Pkcs7)
+                .unwrap(),
+            cbc.encrypt(b"default", &IV).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ciphertext_stealing_variants() {
+        let cbc = AesCbc::new(&AES_KEY_128).unwrap();
+        let plaintext: Vec<u8> = (0..40).collect();
+        let cs1 = cbc
+            .encrypt_padded(&plaintext, &IV, CbcPadding::Cs1)
+            .unwrap();
+        let cs2 = cbc
+            .encrypt_padded(&plaintext, &IV, CbcPadding::Cs2)
+            .unwrap();
+        let cs3 = cbc
+            .encrypt_padded(&plaintext, &IV, CbcPadding::Cs3)
+            .unwrap();
+
+        // The first blocks are plain CBC, and the variants only reorder the
+        // 8-byte partial block and the last full block
+        let raw = cbc
+            .encrypt_padded(&plaintext[..16], &IV, CbcPadding::None)
+            .unwrap();
+        assert_eq!(cs1[..16], raw[..]);
+        assert_eq!(cs1[16..24], cs2[32..]);
+        assert_eq!(cs1[24..], cs2[16..32]);
+        assert_eq!(cs2, cs3);
+
+        // Block-aligned, CS1 and CS2 are plain CBC and CS3 swaps the last two
+        // blocks
+        let plaintext = &plaintext[..32];
+        let raw = cbc
+            .encrypt_padded(plaintext, &IV, CbcPadding::None)
+            .unwrap();
+        assert_eq!(
+            cbc.encrypt_padded(plaintext, &IV, CbcPadding::Cs1).unwrap(),
+            raw
+        );
+        assert_eq!(
+            cbc.encrypt_padded(plaintext, &IV, CbcPadding::Cs2).unwrap(),
+            raw
+        );
+        let cs3 = cbc.encrypt_padded(plaintext, &IV, CbcPadding::Cs3).unwrap();
+        assert_eq!(cs3[..16], raw[16..]);
+        assert_eq!(cs3[16..], raw[..16]);
+    }
+
+    #[test]
+    fn test_iso7816_padding_len() {
+        assert_eq!(iso7816_padding_len(&[0x80; 16]).unwrap(), 1);
+        let mut block = [0u8; 16];
+        block[0] = 0x80;
+        assert_eq!(iso7816_padding_len(&block).unwrap(), 16);
+        block[0] = 0x01;
+        block[5] = 0x80;
+        assert_eq!(iso7816_padding_len(&block).unwrap(), 11);
+
+        let invalid: Vec<Vec<u8>> = vec![
+            vec![],
+            vec![0x80; 17],
+            // No marker
+            vec![0; 16],
+            // Non-zero byte after the marker
+            vec![1, 2, 3, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+            // Last byte of unpadded data
+            vec![0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x7f],
+        ];
+        for padded in invalid {
+            assert_eq!(
+                iso7816_padding_len(&padded).unwrap_err(),
+                "Invalid padding",
+                "{:?}",
+                padded
+            );
+        }
+    }
+
+    #[test]
+    fn test_padding_mode_length_errors() {
+        let cbc = AesCbc::new(&AES_KEY_128).unwrap();
+        assert_eq!(
+            cbc.encrypt_padded(&[0; 17], &IV, CbcPadding::None)
+                .unwrap_err(),
+            "Invalid plaintext length: expected a multiple of 16 bytes"
+        );
+        assert_eq!(
+            cbc.decrypt_padded(&[0; 17], &IV, CbcPadding::None)
+                .unwrap_err(),
+            "Invalid ciphertext length: expected a multiple of 16 bytes"
+        );
+        assert!(cbc.decrypt_padded(&[], &IV, CbcPadding::Iso7816).is_err());
+        for padding in [CbcPadding::Cs1, CbcPadding::Cs2, CbcPadding::Cs3] {
+            assert_eq!(
+                cbc.encrypt_padded(&[0; 15], &IV, padding).unwrap_err(),
+                "Invalid plaintext length: expected at least 16 bytes"
+            );
+            assert_eq!(
+                cbc.decrypt_padded(&[0; 15], &IV, padding).unwrap_err(),
+                "Invalid ciphertext length: expected at least 16 bytes"
+            );
+        }
+
+        // Errors leave the buffer unchanged when encrypting
+        let mut buf = vec![1; 15];
+        assert!(cbc
+            .encrypt_in_place_padded(&mut buf, &IV, CbcPadding::None)
+            .is_err());
+        assert!(cbc
+            .encrypt_in_place_padded(&mut buf, &IV, CbcPadding::Cs1)
+            .is_err());
+        assert_eq!(buf, vec![1; 15]);
+    }
 }
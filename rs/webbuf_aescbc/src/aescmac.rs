@@ -0,0 +1,257 @@
+use crate::aes::AesCipher;
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// AES-CMAC (RFC 4493, NIST SP 800-38B) and AES-CMAC-PRF-128 (RFC 4615)
+
+const BLOCK_SIZE: usize = 16;
+
+// Multiply by x in GF(2^128), without branching on the secret top bit
+fn dbl(block: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    let value = u128::from_be_bytes(*block);
+    let carry = 0u128.wrapping_sub(value >> 127) & 0x87;
+    ((value << 1) ^ carry).to_be_bytes()
+}
+
+fn xor_into(block: &mut [u8; BLOCK_SIZE], other: &[u8; BLOCK_SIZE]) {
+    for (byte, other) in block.iter_mut().zip(other) {
+        *byte ^= other;
+    }
+}
+
+/// Incremental AES-CMAC
+/// Feed data with `update` in pieces of any size, then read the tag with
+/// `finalize`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone)]
+pub struct AesCmac {
+    cipher: AesCipher,
+    k1: [u8; BLOCK_SIZE],
+    k2: [u8; BLOCK_SIZE],
+    state: [u8; BLOCK_SIZE],
+    // The last block is held back until finalize, since it is masked
+    // differently depending on whether it is complete
+    buffer: [u8; BLOCK_SIZE],
+    buffer_len: usize,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl AesCmac {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(aes_key: &[u8]) -> Result<AesCmac, String> {
+        let cipher = AesCipher::new(aes_key)?;
+        // Subkeys from L = AES(K, 0^128)
+        let mut l = [0u8; BLOCK_SIZE];
+        cipher.encrypt_block(&mut l);
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+        Ok(AesCmac {
+            cipher,
+            k1,
+            k2,
+            state: [0; BLOCK_SIZE],
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+        })
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buffer_len == BLOCK_SIZE {
+                xor_into(&mut self.state, &self.buffer);
+                self.cipher.encrypt_block(&mut self.state);
+                self.buffer_len = 0;
+            }
+            let take = (BLOCK_SIZE - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+        }
+    }
+
+    /// Compute the 16-byte tag for the data so far
+    /// The object is left unchanged, so more data can still be added.
+    pub fn finalize(&self) -> Vec<u8> {
+        self.tag().to_vec()
+    }
+
+    /// Compare the tag for the data so far with `tag` in constant time
+    pub fn verify(&self, tag: &[u8]) -> bool {
+        tag.ct_eq(&self.tag()).into()
+    }
+
+    /// Start a new message with the same key
+    pub fn reset(&mut self) {
+        self.state = [0; BLOCK_SIZE];
+        self.buffer = [0; BLOCK_SIZE];
+        self.buffer_len = 0;
+    }
+}
+
+impl AesCmac {
+    pub fn tag(&self) -> [u8; BLOCK_SIZE] {
+        let mut last = [0u8; BLOCK_SIZE];
+        last[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+        if self.buffer_len == BLOCK_SIZE {
+            xor_into(&mut last, &self.k1);
+        } else {
+            // Incomplete (or empty) final block: pad with 10*
+            last[self.buffer_len] = 0x80;
+            xor_into(&mut last, &self.k2);
+        }
+        let mut tag = self.state;
+        xor_into(&mut tag, &last);
+        self.cipher.encrypt_block(&mut tag);
+        tag
+    }
+}
+
+/// Compute the AES-CMAC of `data`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aes_cmac(aes_key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut cmac = AesCmac::new(aes_key)?;
+    cmac.update(data);
+    Ok(cmac.finalize())
+}
+
+/// Check an AES-CMAC tag in constant time
+/// Returns false for an invalid key size as well as a wrong tag.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aes_cmac_verify(aes_key: &[u8], data: &[u8], tag: &[u8]) -> bool {
+    let Ok(mut cmac) = AesCmac::new(aes_key) else {
+        return false;
+    };
+    cmac.update(data);
+    cmac.verify(tag)
+}
+
+/// AES-CMAC-PRF-128 (RFC 4615)
+/// Accepts a key of any length: a 16-byte key is used as is, and any other
+/// key is first reduced to 16 bytes with AES-CMAC under the zero key.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aes_cmac_prf_128(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = if key.len() == BLOCK_SIZE {
+        key.to_vec()
+    } else {
+        aes_cmac(&[0; BLOCK_SIZE], key).expect("zero key is 16 bytes")
+    };
+    aes_cmac(&key, data).expect("derived key is 16 bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        name: String,
+        key: String,
+        msg: String,
+        mac: String,
+    }
+
+    fn hex_to_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("Invalid hex"))
+            .collect()
+    }
+
+    fn load_test_vectors(path: &str) -> Vec<TestVector> {
+        let data = fs::read_to_string(path).expect("Unable to read test vectors file");
+        serde_json::from_str(&data).expect("Error parsing JSON test vectors")
+    }
+
+    #[test]
+    fn test_subkeys_rfc4493() {
+        let key = hex_to_bytes("2b7e151628aed2a6abf7158809cf4f3c");
+        let cmac = AesCmac::new(&key).unwrap();
+        assert_eq!(
+            cmac.k1.to_vec(),
+            hex_to_bytes("fbeed618357133667c85e08f7236a8de")
+        );
+        assert_eq!(
+            cmac.k2.to_vec(),
+            hex_to_bytes("f7ddac306ae266ccf90bc11ee46d513b")
+        );
+    }
+
+    #[test]
+    fn test_aes_cmac_vectors() {
+        for vector in load_test_vectors("vectors/vectors-cmac.json") {
+            let key = hex_to_bytes(&vector.key);
+            let msg = hex_to_bytes(&vector.msg);
+            let mac = hex_to_bytes(&vector.mac);
+            assert_eq!(aes_cmac(&key, &msg).unwrap(), mac, "{}", vector.name);
+            assert!(aes_cmac_verify(&key, &msg, &mac), "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_incremental_update_matches_one_shot() {
+        for vector in load_test_vectors("vectors/vectors-cmac.json") {
+            let key = hex_to_bytes(&vector.key);
+            let msg = hex_to_bytes(&vector.msg);
+            let mac = hex_to_bytes(&vector.mac);
+            for chunk_size in [1, 7, 15, 16, 17, 64] {
+                let mut cmac = AesCmac::new(&key).unwrap();
+                for chunk in msg.chunks(chunk_size) {
+                    cmac.update(chunk);
+                }
+                assert_eq!(
+                    cmac.finalize(),
+                    mac,
+                    "{} in {}-byte chunks",
+                    vector.name,
+                    chunk_size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_finalize_and_reset() {
+        let key = [0x2b; 16];
+        let mut cmac = AesCmac::new(&key).unwrap();
+        cmac.update(b"hello ");
+        assert_eq!(cmac.finalize(), aes_cmac(&key, b"hello ").unwrap());
+        // finalize does not consume the state
+        cmac.update(b"world");
+        assert_eq!(cmac.finalize(), aes_cmac(&key, b"hello world").unwrap());
+
+        cmac.reset();
+        assert_eq!(cmac.finalize(), aes_cmac(&key, b"").unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_tags() {
+        let key = [0x2b; 16];
+        let tag = aes_cmac(&key, b"message").unwrap();
+        for pos in 0..tag.len() {
+            let mut wrong = tag.clone();
+            wrong[pos] ^= 0x01;
+            assert!(!aes_cmac_verify(&key, b"message", &wrong));
+        }
+        assert!(!aes_cmac_verify(&key, b"message", &tag[..15]));
+        assert!(!aes_cmac_verify(&key, b"Message", &tag));
+        assert!(!aes_cmac_verify(&[0x2b; 10], b"message", &tag));
+        assert!(AesCmac::new(&[0; 10]).is_err());
+    }
+
+    #[test]
+    fn test_aes_cmac_prf_128_vectors() {
+        for vector in load_test_vectors("vectors/vectors-cmac-prf.json") {
+            let key = hex_to_bytes(&vector.key);
+            let msg = hex_to_bytes(&vector.msg);
+            let mac = hex_to_bytes(&vector.mac);
+            assert_eq!(aes_cmac_prf_128(&key, &msg), mac, "{}", vector.name);
+        }
+        // Keys of any length, including empty, are accepted
+        assert_eq!(aes_cmac_prf_128(&[], b"data").len(), 16);
+        assert_eq!(aes_cmac_prf_128(&[7; 100], b"data").len(), 16);
+    }
+}
@@ -0,0 +1,295 @@
+use crate::aes::{AesCipher, Block};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const BLOCK_SIZE: usize = 16;
+
+// Keystream blocks generated per batch, so the aes crate can encrypt them in
+// parallel
+const PAR_BLOCKS: usize = 8;
+
+// AES-CTR (NIST SP 800-38A)
+//
+// The 16-byte IV is the initial counter block. Only its low `counter_bits`
+// bits are incremented, as a big-endian integer; the bits above them are a
+// fixed nonce. Once the counter would return to its initial value the
+// keystream would repeat, so processing past that point is an error.
+
+/// Seekable AES-CTR keystream
+/// Encryption and decryption are the same operation. `seek` moves to any
+/// byte offset, so parts of a message can be processed in any order.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone)]
+pub struct AesCtr {
+    cipher: AesCipher,
+    iv: u128,
+    counter_mask: u128,
+    pos: u64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl AesCtr {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(aes_key: &[u8], iv: &[u8], counter_bits: u32) -> Result<AesCtr, String> {
+        let iv: [u8; BLOCK_SIZE] = iv.try_into().map_err(|_| "Invalid IV size".to_string())?;
+        let counter_mask = match counter_bits {
+            32 => u32::MAX as u128,
+            64 => u64::MAX as u128,
+            128 => u128::MAX,
+            _ => return Err("Invalid counter width: expected 32, 64, or 128 bits".to_string()),
+        };
+        Ok(AesCtr {
+            cipher: AesCipher::new(aes_key)?,
+            iv: u128::from_be_bytes(iv),
+            counter_mask,
+            pos: 0,
+        })
+    }
+
+    /// Current byte offset into the keystream
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Move to a byte offset in the keystream
+    pub fn seek(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+
+    /// XOR `data` with the keystream at the current position and advance
+    pub fn process(&mut self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut buf = data.to_vec();
+        self.apply_keystream(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl AesCtr {
+    /// XOR `buf` in place with the keystream at the current position and
+    /// advance
+    /// Fails without modifying `buf` if the counter would wrap.
+    pub fn apply_keystream(&mut self, buf: &mut [u8]) -> Result<(), String> {
+        let end = self
+            .pos
+            .checked_add(buf.len() as u64)
+            .ok_or_else(|| "Keystream exhausted: counter would wrap".to_string())?;
+        let blocks_needed = (end as u128).div_ceil(BLOCK_SIZE as u128);
+        if self.counter_mask != u128::MAX && blocks_needed > self.counter_mask + 1 {
+            return Err("Keystream exhausted: counter would wrap".to_string());
+        }
+
+        let mut keystream = [Block::default(); PAR_BLOCKS];
+        let mut done = 0;
+        while done < buf.len() {
+            let block_index = self.pos / BLOCK_SIZE as u64;
+            let skip = (self.pos % BLOCK_SIZE as u64) as usize;
+            let remaining = buf.len() - done;
+            let n = (skip + remaining).div_ceil(BLOCK_SIZE).min(PAR_BLOCKS);
+
+            for (i, block) in keystream[..n].iter_mut().enumerate() {
+                block.copy_from_slice(&self.counter_block(block_index + i as u64));
+            }
+            self.cipher.encrypt_blocks(&mut keystream[..n]);
+
+            let take = (n * BLOCK_SIZE - skip).min(remaining);
+            let bytes = keystream[..n].iter().flatten().skip(skip);
+            for (byte, key) in buf[done..done + take].iter_mut().zip(bytes) {
+                *byte ^= key;
+            }
+            done += take;
+            self.pos += take as u64;
+        }
+        Ok(())
+    }
+
+    fn counter_block(&self, block_index: u64) -> [u8; BLOCK_SIZE] {
+        let counter = (self.iv & self.counter_mask).wrapping_add(block_index as u128);
+        ((self.iv & !self.counter_mask) | (counter & self.counter_mask)).to_be_bytes()
+    }
+}
+
+// AES-CTR Encrypt
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aesctr_encrypt(
+    plaintext: &[u8],
+    aes_key: &[u8],
+    iv: &[u8],
+    counter_bits: u32,
+) -> Result<Vec<u8>, String> {
+    AesCtr::new(aes_key, iv, counter_bits)?.process(plaintext)
+}
+
+// AES-CTR Decrypt
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aesctr_decrypt(
+    ciphertext: &[u8],
+    aes_key: &[u8],
+    iv: &[u8],
+    counter_bits: u32,
+) -> Result<Vec<u8>, String> {
+    AesCtr::new(aes_key, iv, counter_bits)?.process(ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes::aes_encrypt;
+    use serde::Deserialize;
+    use std::fs;
+
+    const KEY: [u8; 16] = [0x42; 16];
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        name: String,
+        key: String,
+        iv: String,
+        pt: String,
+        ct: String,
+    }
+
+    fn hex_to_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("Invalid hex"))
+            .collect()
+    }
+
+    // Keystream built from single-block aes_encrypt calls on explicit
+    // counter blocks
+    fn reference_keystream(key: &[u8], counter_blocks: &[[u8; 16]]) -> Vec<u8> {
+        counter_blocks
+            .iter()
+            .flat_map(|block| aes_encrypt(key, block).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_aes_ctr_with_nist_vectors() {
+        let data = fs::read_to_string("vectors/vectors-ctr.json")
+            .expect("Unable to read test vectors file");
+        let vectors: Vec<TestVector> =
+            serde_json::from_str(&data).expect("Error parsing JSON test vectors");
+
+        for vector in vectors {
+            let key = hex_to_bytes(&vector.key);
+            let iv = hex_to_bytes(&vector.iv);
+            let plaintext = hex_to_bytes(&vector.pt);
+            let ciphertext = hex_to_bytes(&vector.ct);
+
+            // The counter never carries past 32 bits, so every width agrees
+            for counter_bits in [32, 64, 128] {
+                assert_eq!(
+                    aesctr_encrypt(&plaintext, &key, &iv, counter_bits).unwrap(),
+                    ciphertext,
+                    "{}",
+                    vector.name
+                );
+                assert_eq!(
+                    aesctr_decrypt(&ciphertext, &key, &iv, counter_bits).unwrap(),
+                    plaintext,
+                    "{}",
+                    vector.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_counter_width_wraps_within_its_bits() {
+        let mut iv = [0xaa; 16];
+        iv[8..].copy_from_slice(&[0xff; 8]);
+
+        let mut after_32 = iv;
+        after_32[12..].copy_from_slice(&[0; 4]);
+        let mut after_64 = iv;
+        after_64[8..].copy_from_slice(&[0; 8]);
+        let mut after_128 = [0xaa; 16];
+        after_128[7] = 0xab;
+        after_128[8..].copy_from_slice(&[0; 8]);
+
+        for (counter_bits, next) in [(32, after_32), (64, after_64), (128, after_128)] {
+            let keystream = aesctr_encrypt(&[0; 32], &KEY, &iv, counter_bits).unwrap();
+            assert_eq!(
+                keystream,
+                reference_keystream(&KEY, &[iv, next]),
+                "{} bits",
+                counter_bits
+            );
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let iv = [3u8; 16];
+        let plaintext: Vec<u8> = (0..1000).map(|i| (i * 7) as u8).collect();
+        let expected = aesctr_encrypt(&plaintext, &KEY, &iv, 64).unwrap();
+
+        for chunk_size in [1, 5, 16, 17, 127, 128, 129] {
+            let mut ctr = AesCtr::new(&KEY, &iv, 64).unwrap();
+            let mut buf = plaintext.clone();
+            for chunk in buf.chunks_mut(chunk_size) {
+                ctr.apply_keystream(chunk).unwrap();
+            }
+            assert_eq!(buf, expected, "chunk size {}", chunk_size);
+            assert_eq!(ctr.position(), 1000);
+        }
+    }
+
+    #[test]
+    fn test_seek_gives_random_access() {
+        let iv = [9u8; 16];
+        let plaintext: Vec<u8> = (0..500).map(|i| i as u8).collect();
+        let ciphertext = aesctr_encrypt(&plaintext, &KEY, &iv, 128).unwrap();
+
+        let mut ctr = AesCtr::new(&KEY, &iv, 128).unwrap();
+        for (start, end) in [(300, 500), (0, 1), (17, 250), (33, 33), (499, 500)] {
+            ctr.seek(start as u64);
+            assert_eq!(
+                ctr.process(&ciphertext[start..end]).unwrap(),
+                &plaintext[start..end],
+                "{}..{}",
+                start,
+                end
+            );
+            assert_eq!(ctr.position(), end as u64);
+        }
+    }
+
+    #[test]
+    fn test_keystream_exhausted() {
+        let iv = [0u8; 16];
+        let limit = (1u64 << 32) * 16;
+        let mut ctr = AesCtr::new(&KEY, &iv, 32).unwrap();
+
+        // The last block of a 32-bit counter uses counter value 0xffffffff
+        ctr.seek(limit - 8);
+        let mut buf = [0u8; 8];
+        ctr.apply_keystream(&mut buf).unwrap();
+        let mut last_block = iv;
+        last_block[12..].copy_from_slice(&[0xff; 4]);
+        assert_eq!(buf, reference_keystream(&KEY, &[last_block])[8..]);
+
+        // One more byte would reuse the first block of keystream
+        let mut buf = [0u8; 1];
+        assert!(ctr.apply_keystream(&mut buf).is_err());
+        assert_eq!(buf, [0]);
+        ctr.seek(limit - 8);
+        assert!(ctr.process(&[0; 9]).is_err());
+
+        // 64-bit and wider counters only run out with the byte offset
+        let mut ctr = AesCtr::new(&KEY, &iv, 64).unwrap();
+        ctr.seek(u64::MAX);
+        assert!(ctr.process(&[0; 1]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let iv = [0u8; 16];
+        assert!(AesCtr::new(&KEY, &iv, 16).is_err());
+        assert!(AesCtr::new(&KEY, &iv[..8], 128).is_err());
+        assert!(AesCtr::new(&[0; 10], &iv, 128).is_err());
+        assert!(aesctr_encrypt(b"data", &KEY, &iv, 0).is_err());
+    }
+}
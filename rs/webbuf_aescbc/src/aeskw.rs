@@ -0,0 +1,297 @@
+use crate::aes::AesCipher;
+use subtle::{Choice, ConstantTimeEq, ConstantTimeGreater};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// AES Key Wrap (RFC 3394) and AES Key Wrap with Padding (RFC 5649)
+//
+// Both wrap key data in 64-bit semiblocks under a key-encryption key (KEK)
+// of 16, 24 or 32 bytes. Unwrapping recovers an integrity value that must
+// match; it is compared in constant time, and every failure returns the same
+// error so the unwrapped data is never released on a mismatch.
+
+const KW_IV: [u8; 8] = [0xa6; 8];
+const KWP_IV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+const SEMIBLOCK: usize = 8;
+
+// Wrapping process W (RFC 3394 section 2.2.1, index based)
+fn wrap_semiblocks(cipher: &AesCipher, iv: [u8; 8], data: &[u8]) -> Vec<u8> {
+    let n = data.len() / SEMIBLOCK;
+    let mut out = vec![0u8; SEMIBLOCK + data.len()];
+    out[SEMIBLOCK..].copy_from_slice(data);
+
+    let mut a = u64::from_be_bytes(iv);
+    let mut block = [0u8; 16];
+    for j in 0..6 {
+        for i in 1..=n {
+            let r = &mut out[i * SEMIBLOCK..(i + 1) * SEMIBLOCK];
+            block[..8].copy_from_slice(&a.to_be_bytes());
+            block[8..].copy_from_slice(r);
+            cipher.encrypt_block(&mut block);
+            a = u64::from_be_bytes(block[..8].try_into().unwrap()) ^ (n * j + i) as u64;
+            r.copy_from_slice(&block[8..]);
+        }
+    }
+    out[..SEMIBLOCK].copy_from_slice(&a.to_be_bytes());
+    out
+}
+
+// Unwrapping process W^-1 (RFC 3394 section 2.2.2, index based)
+// Returns the integrity value and the unwrapped data, unchecked.
+fn unwrap_semiblocks(cipher: &AesCipher, wrapped: &[u8]) -> ([u8; 8], Vec<u8>) {
+    let n = wrapped.len() / SEMIBLOCK - 1;
+    let mut a = u64::from_be_bytes(wrapped[..SEMIBLOCK].try_into().unwrap());
+    let mut out = wrapped[SEMIBLOCK..].to_vec();
+
+    let mut block = [0u8; 16];
+    for j in (0..6).rev() {
+        for i in (1..=n).rev() {
+            let r = &mut out[(i - 1) * SEMIBLOCK..i * SEMIBLOCK];
+            block[..8].copy_from_slice(&(a ^ (n * j + i) as u64).to_be_bytes());
+            block[8..].copy_from_slice(r);
+            cipher.decrypt_block(&mut block);
+            a = u64::from_be_bytes(block[..8].try_into().unwrap());
+            r.copy_from_slice(&block[8..]);
+        }
+    }
+    (a.to_be_bytes(), out)
+}
+
+fn integrity_error() -> String {
+    "Key unwrap failed: integrity check failed".to_string()
+}
+
+/// Wrap key data with AES-KW (RFC 3394)
+/// The key data must be a multiple of 8 bytes and at least 16 bytes.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aeskw_wrap(key_data: &[u8], kek: &[u8]) -> Result<Vec<u8>, String> {
+    if key_data.len() < 16 || !key_data.len().is_multiple_of(SEMIBLOCK) {
+        return Err(
+            "Invalid key data length: expected a multiple of 8 bytes, at least 16".to_string(),
+        );
+    }
+    let cipher = AesCipher::new(kek)?;
+    Ok(wrap_semiblocks(&cipher, KW_IV, key_data))
+}
+
+/// Unwrap key data wrapped with AES-KW (RFC 3394)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aeskw_unwrap(wrapped: &[u8], kek: &[u8]) -> Result<Vec<u8>, String> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(SEMIBLOCK) {
+        return Err(
+            "Invalid wrapped key length: expected a multiple of 8 bytes, at least 24".to_string(),
+        );
+    }
+    let cipher = AesCipher::new(kek)?;
+    let (a, key_data) = unwrap_semiblocks(&cipher, wrapped);
+    if !bool::from(a.ct_eq(&KW_IV)) {
+        return Err(integrity_error());
+    }
+    Ok(key_data)
+}
+
+/// Wrap key data of any length with AES-KWP (RFC 5649)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aeskwp_wrap(key_data: &[u8], kek: &[u8]) -> Result<Vec<u8>, String> {
+    let mli = u32::try_from(key_data.len())
+        .ok()
+        .filter(|&len| len > 0)
+        .ok_or_else(|| "Invalid key data length: expected 1 to 2^32 - 1 bytes".to_string())?;
+    let cipher = AesCipher::new(kek)?;
+
+    let mut aiv = [0u8; 8];
+    aiv[..4].copy_from_slice(&KWP_IV_PREFIX);
+    aiv[4..].copy_from_slice(&mli.to_be_bytes());
+    let mut padded = key_data.to_vec();
+    padded.resize(key_data.len().div_ceil(SEMIBLOCK) * SEMIBLOCK, 0);
+
+    // A single semiblock is encrypted directly with the AIV
+    if padded.len() == SEMIBLOCK {
+        let mut block = [0u8; 16];
+        block[..8].copy_from_slice(&aiv);
+        block[8..].copy_from_slice(&padded);
+        cipher.encrypt_block(&mut block);
+        return Ok(block.to_vec());
+    }
+    Ok(wrap_semiblocks(&cipher, aiv, &padded))
+}
+
+/// Unwrap key data wrapped with AES-KWP (RFC 5649)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aeskwp_unwrap(wrapped: &[u8], kek: &[u8]) -> Result<Vec<u8>, String> {
+    if wrapped.len() < 16 || !wrapped.len().is_multiple_of(SEMIBLOCK) {
+        return Err(
+            "Invalid wrapped key length: expected a multiple of 8 bytes, at least 16".to_string(),
+        );
+    }
+    let cipher = AesCipher::new(kek)?;
+
+    let (aiv, mut padded) = if wrapped.len() == 16 {
+        let mut block: [u8; 16] = wrapped.try_into().unwrap();
+        cipher.decrypt_block(&mut block);
+        (block[..8].try_into().unwrap(), block[8..].to_vec())
+    } else {
+        unwrap_semiblocks(&cipher, wrapped)
+    };
+
+    // The AIV prefix must match, the message length indicator must fall in
+    // the last semiblock, and the padding after it must be zero
+    let len = padded.len() as u64;
+    let mli = u32::from_be_bytes(aiv[4..].try_into().unwrap()) as u64;
+    let mut valid: Choice = aiv[..4].ct_eq(&KWP_IV_PREFIX);
+    valid &= mli.ct_gt(&(len - SEMIBLOCK as u64)) & !mli.ct_gt(&len);
+    for (i, byte) in padded.iter().enumerate().skip(padded.len() - SEMIBLOCK) {
+        let in_padding = !mli.ct_gt(&(i as u64));
+        valid &= !in_padding | byte.ct_eq(&0);
+    }
+
+    if !bool::from(valid) {
+        return Err(integrity_error());
+    }
+    padded.truncate(mli as usize);
+    Ok(padded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+
+    const KEK: [u8; 16] = [0x0f; 16];
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        name: String,
+        kek: String,
+        key: String,
+        wrapped: String,
+    }
+
+    fn hex_to_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("Invalid hex"))
+            .collect()
+    }
+
+    fn load_test_vectors(path: &str) -> Vec<TestVector> {
+        let data = fs::read_to_string(path).expect("Unable to read test vectors file");
+        serde_json::from_str(&data).expect("Error parsing JSON test vectors")
+    }
+
+    #[test]
+    fn test_aeskw_rfc3394_vectors() {
+        for vector in load_test_vectors("vectors/vectors-kw.json") {
+            let kek = hex_to_bytes(&vector.kek);
+            let key = hex_to_bytes(&vector.key);
+            let wrapped = hex_to_bytes(&vector.wrapped);
+            assert_eq!(aeskw_wrap(&key, &kek).unwrap(), wrapped, "{}", vector.name);
+            assert_eq!(
+                aeskw_unwrap(&wrapped, &kek).unwrap(),
+                key,
+                "{}",
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_aeskwp_rfc5649_vectors() {
+        for vector in load_test_vectors("vectors/vectors-kwp.json") {
+            let kek = hex_to_bytes(&vector.kek);
+            let key = hex_to_bytes(&vector.key);
+            let wrapped = hex_to_bytes(&vector.wrapped);
+            assert_eq!(aeskwp_wrap(&key, &kek).unwrap(), wrapped, "{}", vector.name);
+            assert_eq!(
+                aeskwp_unwrap(&wrapped, &kek).unwrap(),
+                key,
+                "{}",
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_kwp_matches_kw_for_aligned_data() {
+        // Apart from the integrity value, KWP of 8n bytes is KW
+        let key: Vec<u8> = (0..32).collect();
+        let kw = aeskw_wrap(&key, &KEK).unwrap();
+        let kwp = aeskwp_wrap(&key, &KEK).unwrap();
+        assert_eq!(kw.len(), kwp.len());
+        assert_ne!(kw, kwp);
+        assert_eq!(aeskwp_unwrap(&kwp, &KEK).unwrap(), key);
+    }
+
+    #[test]
+    fn test_unwrap_rejects_tampering() {
+        let key: Vec<u8> = (0..24).collect();
+        let kw = aeskw_wrap(&key, &KEK).unwrap();
+        let kwp = aeskwp_wrap(&key[..5], &KEK).unwrap();
+        for pos in 0..kw.len() {
+            let mut tampered = kw.clone();
+            tampered[pos] ^= 0x80;
+            assert_eq!(
+                aeskw_unwrap(&tampered, &KEK).unwrap_err(),
+                "Key unwrap failed: integrity check failed"
+            );
+        }
+        for pos in 0..kwp.len() {
+            let mut tampered = kwp.clone();
+            tampered[pos] ^= 0x80;
+            assert_eq!(
+                aeskwp_unwrap(&tampered, &KEK).unwrap_err(),
+                "Key unwrap failed: integrity check failed"
+            );
+        }
+
+        let mut wrong_kek = KEK;
+        wrong_kek[15] ^= 1;
+        assert!(aeskw_unwrap(&kw, &wrong_kek).is_err());
+        assert!(aeskwp_unwrap(&kwp, &wrong_kek).is_err());
+
+        // The two schemes use different integrity values
+        assert!(aeskwp_unwrap(&kw, &KEK).is_err());
+        assert!(aeskw_unwrap(&aeskwp_wrap(&key, &KEK).unwrap(), &KEK).is_err());
+    }
+
+    // Encrypt a single KWP block with a chosen AIV and payload
+    fn kwp_block(mli: u32, payload: [u8; 8]) -> Vec<u8> {
+        let mut block = [0u8; 16];
+        block[..4].copy_from_slice(&KWP_IV_PREFIX);
+        block[4..8].copy_from_slice(&mli.to_be_bytes());
+        block[8..].copy_from_slice(&payload);
+        AesCipher::new(&KEK).unwrap().encrypt_block(&mut block);
+        block.to_vec()
+    }
+
+    #[test]
+    fn test_aeskwp_unwrap_checks_length_and_padding() {
+        assert_eq!(
+            aeskwp_unwrap(&kwp_block(5, [1, 2, 3, 4, 5, 0, 0, 0]), &KEK).unwrap(),
+            [1, 2, 3, 4, 5]
+        );
+        assert_eq!(aeskwp_unwrap(&kwp_block(8, [1; 8]), &KEK).unwrap(), [1; 8]);
+        // Non-zero padding
+        assert!(aeskwp_unwrap(&kwp_block(5, [1, 2, 3, 4, 5, 0, 1, 0]), &KEK).is_err());
+        assert!(aeskwp_unwrap(&kwp_block(7, [1; 8]), &KEK).is_err());
+        // Length indicator outside the last semiblock
+        assert!(aeskwp_unwrap(&kwp_block(0, [0; 8]), &KEK).is_err());
+        assert!(aeskwp_unwrap(&kwp_block(9, [1; 8]), &KEK).is_err());
+        assert!(aeskwp_unwrap(&kwp_block(u32::MAX, [1; 8]), &KEK).is_err());
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        assert!(aeskw_wrap(&[0; 8], &KEK).is_err());
+        assert!(aeskw_wrap(&[0; 17], &KEK).is_err());
+        assert!(aeskw_unwrap(&[0; 16], &KEK).is_err());
+        assert!(aeskw_unwrap(&[0; 25], &KEK).is_err());
+        assert!(aeskwp_wrap(&[], &KEK).is_err());
+        assert!(aeskwp_unwrap(&[0; 8], &KEK).is_err());
+        assert!(aeskwp_unwrap(&[0; 20], &KEK).is_err());
+        assert!(aeskw_wrap(&[0; 16], &[0; 15]).is_err());
+        assert!(aeskwp_wrap(&[0; 16], &[0; 15]).is_err());
+    }
+}
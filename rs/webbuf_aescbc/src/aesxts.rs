@@ -0,0 +1,323 @@
+use crate::aes::{AesCipher, Block};
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const BLOCK_SIZE: usize = 16;
+
+// Blocks encrypted per batch, so the aes crate can process them in parallel
+const PAR_BLOCKS: usize = 8;
+
+// IEEE 1619 and SP 800-38E limit a data unit to 2^20 blocks
+const MAX_DATA_UNIT_LEN: usize = (1 << 20) * BLOCK_SIZE;
+
+// AES-XTS (IEEE 1619, NIST SP 800-38E)
+//
+// The combined key is two AES keys of equal size: the first encrypts the
+// data, the second encrypts the tweak. The tweak is the sector (data unit)
+// number as a 128-bit little-endian integer, and is multiplied by x in
+// GF(2^128) for each following block. A data unit that is not a multiple of
+// 16 bytes uses ciphertext stealing, so the ciphertext is the same length
+// as the plaintext.
+//
+// As in IEEE 1619 and SP 800-38E, the two halves of the key must differ and
+// a data unit is at most 2^20 blocks (16 MiB).
+
+fn mul_alpha(tweak: u128) -> u128 {
+    (tweak << 1) ^ (0u128.wrapping_sub(tweak >> 127) & 0x87)
+}
+
+/// AES-XTS with a fixed key, for encrypting many sectors
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone)]
+pub struct AesXts {
+    data_cipher: AesCipher,
+    tweak_cipher: AesCipher,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl AesXts {
+    /// `key` is 32 bytes for AES-128-XTS or 64 bytes for AES-256-XTS
+    /// Its two halves must differ.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(key: &[u8]) -> Result<AesXts, String> {
+        if key.len() != 32 && key.len() != 64 {
+            return Err("Invalid key size: expected 32 or 64 bytes".to_string());
+        }
+        let (data_key, tweak_key) = key.split_at(key.len() / 2);
+        if bool::from(data_key.ct_eq(tweak_key)) {
+            return Err("Invalid key: the two halves must differ".to_string());
+        }
+        AesXts::from_key(key)
+    }
+
+    pub fn encrypt_sector(&self, plaintext: &[u8], sector: u64) -> Result<Vec<u8>, String> {
+        let mut buf = plaintext.to_vec();
+        self.encrypt_in_place(&mut buf, sector)?;
+        Ok(buf)
+    }
+
+    pub fn decrypt_sector(&self, ciphertext: &[u8], sector: u64) -> Result<Vec<u8>, String> {
+        let mut buf = ciphertext.to_vec();
+        self.decrypt_in_place(&mut buf, sector)?;
+        Ok(buf)
+    }
+}
+
+impl AesXts {
+    // Without the check on the key halves, which only IEEE 1619 vector 1
+    // needs
+    fn from_key(key: &[u8]) -> Result<AesXts, String> {
+        let (data_key, tweak_key) = key.split_at(key.len() / 2);
+        Ok(AesXts {
+            data_cipher: AesCipher::new(data_key)?,
+            tweak_cipher: AesCipher::new(tweak_key)?,
+        })
+    }
+
+    /// Encrypt one data unit of 16 bytes to 16 MiB in place
+    pub fn encrypt_in_place(&self, buf: &mut [u8], sector: u64) -> Result<(), String> {
+        let (full, partial) = split_data_unit(buf.len())?;
+        let tweak = self.process_blocks(&mut buf[..full], self.initial_tweak(sector), true);
+        if partial == 0 {
+            return Ok(());
+        }
+
+        // Ciphertext stealing: the last full block is encrypted as usual,
+        // its head becomes the final partial block, and the partial
+        // plaintext plus its tail is encrypted in its place
+        let last = full..full + BLOCK_SIZE;
+        let next_tweak = self.process_blocks(&mut buf[last.clone()], tweak, true);
+        swap_stolen(buf, full, partial);
+        self.process_blocks(&mut buf[last], next_tweak, true);
+        Ok(())
+    }
+
+    /// Decrypt one data unit of 16 bytes to 16 MiB in place
+    pub fn decrypt_in_place(&self, buf: &mut [u8], sector: u64) -> Result<(), String> {
+        let (full, partial) = split_data_unit(buf.len())?;
+        let tweak = self.process_blocks(&mut buf[..full], self.initial_tweak(sector), false);
+        if partial == 0 {
+            return Ok(());
+        }
+
+        // The two final blocks were encrypted with the tweaks in reverse
+        // order, so undo them the same way
+        let last = full..full + BLOCK_SIZE;
+        self.process_blocks(&mut buf[last.clone()], mul_alpha(tweak), false);
+        swap_stolen(buf, full, partial);
+        self.process_blocks(&mut buf[last], tweak, false);
+        Ok(())
+    }
+
+    fn initial_tweak(&self, sector: u64) -> u128 {
+        let mut block = (sector as u128).to_le_bytes();
+        self.tweak_cipher.encrypt_block(&mut block);
+        u128::from_le_bytes(block)
+    }
+
+    // En/decrypt whole blocks starting at `tweak` and return the tweak for
+    // the block after them
+    fn process_blocks(&self, buf: &mut [u8], mut tweak: u128, encrypt: bool) -> u128 {
+        let mut blocks = [Block::default(); PAR_BLOCKS];
+        let mut tweaks = [[0u8; BLOCK_SIZE]; PAR_BLOCKS];
+        for chunk in buf.chunks_mut(BLOCK_SIZE * PAR_BLOCKS) {
+            let n = chunk.len() / BLOCK_SIZE;
+            for i in 0..n {
+                tweaks[i] = tweak.to_le_bytes();
+                tweak = mul_alpha(tweak);
+                let data = &chunk[i * BLOCK_SIZE..(i + 1) * BLOCK_SIZE];
+                for (byte, (d, t)) in blocks[i].iter_mut().zip(data.iter().zip(&tweaks[i])) {
+                    *byte = d ^ t;
+                }
+            }
+            if encrypt {
+                self.data_cipher.encrypt_blocks(&mut blocks[..n]);
+            } else {
+                self.data_cipher.decrypt_blocks(&mut blocks[..n]);
+            }
+            for i in 0..n {
+                let out = &mut chunk[i * BLOCK_SIZE..(i + 1) * BLOCK_SIZE];
+                for (byte, (b, t)) in out.iter_mut().zip(blocks[i].iter().zip(&tweaks[i])) {
+                    *byte = b ^ t;
+                }
+            }
+        }
+        tweak
+    }
+}
+
+// Split a data unit into the bytes handled block by block and the length of
+// a trailing partial block. With a partial block, the last full block is
+// left out too, since the two are processed together.
+fn split_data_unit(len: usize) -> Result<(usize, usize), String> {
+    if len < BLOCK_SIZE {
+        return Err("Invalid data unit length: expected at least 16 bytes".to_string());
+    }
+    if len > MAX_DATA_UNIT_LEN {
+        return Err("Invalid data unit length: expected at most 2^20 blocks".to_string());
+    }
+    let partial = len % BLOCK_SIZE;
+    if partial == 0 {
+        Ok((len, 0))
+    } else {
+        Ok((len - BLOCK_SIZE - partial, partial))
+    }
+}
+
+// Exchange the partial block with the head of the last full block
+fn swap_stolen(buf: &mut [u8], full: usize, partial: usize) {
+    for i in 0..partial {
+        buf.swap(full + i, full + BLOCK_SIZE + i);
+    }
+}
+
+// AES-XTS Encrypt
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aesxts_encrypt(plaintext: &[u8], key: &[u8], sector: u64) -> Result<Vec<u8>, String> {
+    AesXts::new(key)?.encrypt_sector(plaintext, sector)
+}
+
+// AES-XTS Decrypt
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aesxts_decrypt(ciphertext: &[u8], key: &[u8], sector: u64) -> Result<Vec<u8>, String> {
+    AesXts::new(key)?.decrypt_sector(ciphertext, sector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+
+    // The halves differ for both AES-128-XTS (first 32 bytes) and AES-256-XTS
+    const KEY: [u8; 64] = {
+        let mut key = [0u8; 64];
+        let mut i = 0;
+        while i < key.len() {
+            key[i] = (i as u8).wrapping_mul(37).wrapping_add(11);
+            i += 1;
+        }
+        key
+    };
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        name: String,
+        key: String,
+        sector: u64,
+        pt: String,
+        ct: String,
+    }
+
+    fn hex_to_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("Invalid hex"))
+            .collect()
+    }
+
+    #[test]
+    fn test_aes_xts_with_ieee_vectors() {
+        let data = fs::read_to_string("vectors/vectors-xts.json")
+            .expect("Unable to read test vectors file");
+        let vectors: Vec<TestVector> =
+            serde_json::from_str(&data).expect("Error parsing JSON test vectors");
+
+        for vector in vectors {
+            let key = hex_to_bytes(&vector.key);
+            let plaintext = hex_to_bytes(&vector.pt);
+            let ciphertext = hex_to_bytes(&vector.ct);
+
+            // Vector 1 uses an all-zero key, whose halves are equal
+            let xts = if vector.name == "IEEE 1619 Vector 1" {
+                assert!(AesXts::new(&key).is_err());
+                AesXts::from_key(&key).unwrap()
+            } else {
+                AesXts::new(&key).unwrap()
+            };
+            assert_eq!(
+                xts.encrypt_sector(&plaintext, vector.sector).unwrap(),
+                ciphertext,
+                "{}",
+                vector.name
+            );
+            assert_eq!(
+                xts.decrypt_sector(&ciphertext, vector.sector).unwrap(),
+                plaintext,
+                "{}",
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip_all_lengths() {
+        for key in [&KEY[..32], &KEY[..]] {
+            let xts = AesXts::new(key).unwrap();
+            for len in 16..=200 {
+                let plaintext: Vec<u8> = (0..len).map(|i| (i * 13) as u8).collect();
+                let mut buf = plaintext.clone();
+                xts.encrypt_in_place(&mut buf, len as u64).unwrap();
+                assert_ne!(buf, plaintext, "length {}", len);
+                xts.decrypt_in_place(&mut buf, len as u64).unwrap();
+                assert_eq!(buf, plaintext, "length {}", len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ciphertext_stealing_keeps_full_blocks() {
+        // Only the last full block and the partial block differ from the
+        // block-aligned encryption of the same prefix
+        let xts = AesXts::new(&KEY).unwrap();
+        let plaintext = [7u8; 16 * 4 + 5];
+        let aligned = xts.encrypt_sector(&plaintext[..64], 9).unwrap();
+        let stolen = xts.encrypt_sector(&plaintext, 9).unwrap();
+        assert_eq!(stolen.len(), plaintext.len());
+        assert_eq!(stolen[..48], aligned[..48]);
+        assert_eq!(stolen[64..], aligned[48..53]);
+    }
+
+    #[test]
+    fn test_sector_is_the_tweak() {
+        let xts = AesXts::new(&KEY).unwrap();
+        let plaintext = [0u8; 512];
+        let sector_0 = xts.encrypt_sector(&plaintext, 0).unwrap();
+        let sector_1 = xts.encrypt_sector(&plaintext, 1).unwrap();
+        assert_ne!(sector_0, sector_1);
+        assert_ne!(
+            xts.decrypt_sector(&sector_0, 1).unwrap().as_slice(),
+            &plaintext[..]
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(AesXts::new(&KEY[..16]).is_err());
+        assert!(AesXts::new(&KEY[..48]).is_err());
+        assert!(aesxts_encrypt(&[0; 15], &KEY, 0).is_err());
+        assert!(aesxts_decrypt(&[], &KEY, 0).is_err());
+    }
+
+    #[test]
+    fn test_rejects_equal_key_halves() {
+        for len in [32, 64] {
+            let mut key = KEY[..len].to_vec();
+            key.copy_within(..len / 2, len / 2);
+            assert_eq!(
+                AesXts::new(&key).err().unwrap(),
+                "Invalid key: the two halves must differ"
+            );
+            assert!(aesxts_encrypt(&[0; 16], &key, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_data_unit_limit() {
+        assert!(split_data_unit(MAX_DATA_UNIT_LEN).is_ok());
+        assert!(split_data_unit(MAX_DATA_UNIT_LEN + 1).is_err());
+        assert!(split_data_unit(MAX_DATA_UNIT_LEN + BLOCK_SIZE).is_err());
+    }
+}
@@ -1,2 +1,6 @@
 pub mod aes;
 pub mod aescbc;
+pub mod aescmac;
+pub mod aesctr;
+pub mod aeskw;
+pub mod aesxts;
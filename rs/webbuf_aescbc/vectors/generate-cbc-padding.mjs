@@ -0,0 +1,91 @@
+// Generates vectors-cbc-padding.json for the non-PKCS#7 padding modes, using
+// node:crypto for raw AES-CBC and building the padding and ciphertext
+// stealing on top of it. The "none" vectors are NIST SP 800-38A F.2 and the
+// CS3 vectors include the RFC 3962 Appendix B examples, which the output is
+// checked against.
+//
+// Run from this directory: node generate-cbc-padding.mjs
+
+import { createCipheriv } from "node:crypto";
+import { writeFileSync } from "node:fs";
+
+const fromHex = (s) => Buffer.from(s, "hex");
+
+function rawCbc(key, iv, data) {
+  const cipher = createCipheriv(`aes-${key.length * 8}-cbc`, key, iv);
+  cipher.setAutoPadding(false);
+  return Buffer.concat([cipher.update(data), cipher.final()]);
+}
+
+// 0x80 followed by zeros up to the next block boundary
+function iso7816(key, iv, pt) {
+  const pad = Buffer.alloc(16 - (pt.length % 16));
+  pad[0] = 0x80;
+  return rawCbc(key, iv, Buffer.concat([pt, pad]));
+}
+
+// NIST SP 800-38A Addendum: CBC over the zero-padded plaintext, then drop
+// the unused tail of the second-to-last ciphertext block
+function cs(variant, key, iv, pt) {
+  const d = pt.length % 16 || 16;
+  const n = Math.ceil(pt.length / 16);
+  const full = rawCbc(key, iv, Buffer.concat([pt, Buffer.alloc(n * 16 - pt.length)]));
+  if (n === 1) {
+    return full;
+  }
+  const head = full.subarray(0, (n - 2) * 16);
+  const partial = full.subarray((n - 2) * 16, (n - 2) * 16 + d);
+  const last = full.subarray((n - 1) * 16);
+  const swap = variant === 3 || (variant === 2 && d !== 16);
+  return Buffer.concat(swap ? [head, last, partial] : [head, partial, last]);
+}
+
+const nistKey = fromHex("2b7e151628aed2a6abf7158809cf4f3c");
+const nistIv = fromHex("000102030405060708090a0b0c0d0e0f");
+const nistPt = fromHex(
+  "6bc1bee22e409f96e93d7e117393172a" +
+    "ae2d8a571e03ac9c9eb76fac45af8e51" +
+    "30c81c46a35ce411e5fbc1191a0a52ef" +
+    "f69f2445df4f9b17ad2b417be66c3710",
+);
+const nist256Key = fromHex("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4");
+
+const rfcKey = fromHex("636869636b656e207465726979616b69");
+const rfcIv = Buffer.alloc(16);
+const rfcPt = Buffer.from("I would like the General Gau's Chicken, please, and wonton soup.");
+// Expected RFC 3962 outputs, to check the CS3 construction above
+const rfcCt = {
+  17: "c6353568f2bf8cb4d8a580362da7ff7f97",
+  32: "39312523a78662d5be7fcbcc98ebf5a897687268d6ecccc0c07b25e25ecfe584",
+};
+
+const seq = (len) => Buffer.from(Array.from({ length: len }, (_, i) => i));
+
+const vectors = [];
+const add = (name, padding, key, iv, pt, ct) =>
+  vectors.push({ name, padding, key: key.toString("hex"), iv: iv.toString("hex"), pt: pt.toString("hex"), ct: ct.toString("hex") });
+
+add("SP 800-38A F.2.1 CBC-AES128", "none", nistKey, nistIv, nistPt, rawCbc(nistKey, nistIv, nistPt));
+add("SP 800-38A F.2.5 CBC-AES256", "none", nist256Key, nistIv, nistPt, rawCbc(nist256Key, nistIv, nistPt));
+add("empty", "none", nistKey, nistIv, Buffer.alloc(0), Buffer.alloc(0));
+
+for (const len of [0, 1, 15, 16, 17, 40]) {
+  add(`${len} bytes`, "iso7816", nistKey, nistIv, seq(len), iso7816(nistKey, nistIv, seq(len)));
+}
+
+for (const variant of [1, 2, 3]) {
+  for (const len of [16, 17, 31, 32, 47, 48, 64]) {
+    const pt = rfcPt.subarray(0, len);
+    const ct = cs(variant, rfcKey, rfcIv, pt);
+    if (variant === 3 && rfcCt[len] && ct.toString("hex") !== rfcCt[len]) {
+      throw new Error(`CS3 does not match RFC 3962 for ${len} bytes`);
+    }
+    const name = variant === 3 && len > 16 ? `RFC 3962 ${len} bytes` : `${len} bytes`;
+    add(name, `cs${variant}`, rfcKey, rfcIv, pt, ct);
+  }
+  for (const len of [17, 100]) {
+    add(`AES-256 ${len} bytes`, `cs${variant}`, nist256Key, nistIv, seq(len), cs(variant, nist256Key, nistIv, seq(len)));
+  }
+}
+
+writeFileSync("vectors-cbc-padding.json", JSON.stringify(vectors, null, "\t") + "\n");
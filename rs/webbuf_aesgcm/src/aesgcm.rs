@@ -1,11 +1,172 @@
-use aes_gcm::{
-    aead::{Aead, KeyInit, Payload},
-    Aes128Gcm, Aes256Gcm, Nonce,
+use ghash::{
+    universal_hash::{KeyInit, UniversalHash},
+    GHash,
 };
+use subtle::ConstantTimeEq;
+use webbuf_aescbc::aes::{AesCipher, Block};
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+// AES-GCM (NIST SP 800-38D), built on the AES block cipher from
+// webbuf_aescbc and the GHASH universal hash
+
+const BLOCK_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+
+// Keystream blocks generated per batch, so the aes crate can encrypt them in
+// parallel
+const PAR_BLOCKS: usize = 8;
+
+// The 32-bit counter must not wrap back to J0, which leaves 2^32 - 2 blocks
+const MAX_PLAINTEXT_LEN: u64 = ((1 << 32) - 2) * BLOCK_SIZE as u64;
+
+struct Gcm {
+    cipher: AesCipher,
+    ghash_key: Block,
+}
+
+impl Gcm {
+    fn new(key: &[u8]) -> Result<Self, String> {
+        if ![16, 24, 32].contains(&key.len()) {
+            return Err("Key must be 16, 24, or 32 bytes".to_string());
+        }
+        let cipher = AesCipher::new(key)?;
+        let mut ghash_key = [0u8; BLOCK_SIZE];
+        cipher.encrypt_block(&mut ghash_key);
+        Ok(Gcm {
+            cipher,
+            ghash_key: ghash_key.into(),
+        })
+    }
+
+    // The pre-counter block J0
+    //
+    // A 12-byte IV is used directly, followed by a 32-bit counter of 1. An
+    // IV of any other length is hashed with GHASH, together with its length.
+    fn initial_counter(&self, iv: &[u8]) -> Result<[u8; BLOCK_SIZE], String> {
+        if iv.is_empty() {
+            return Err("IV must not be empty".to_string());
+        }
+        if iv.len() == NONCE_SIZE {
+            let mut j0 = [0u8; BLOCK_SIZE];
+            j0[..NONCE_SIZE].copy_from_slice(iv);
+            j0[BLOCK_SIZE - 1] = 1;
+            return Ok(j0);
+        }
+
+        let mut ghash = GHash::new(&self.ghash_key);
+        ghash.update_padded(iv);
+        let mut lengths = Block::default();
+        lengths[8..].copy_from_slice(&(iv.len() as u64 * 8).to_be_bytes());
+        ghash.update(&[lengths]);
+        Ok(ghash.finalize().into())
+    }
+
+    // XOR `buf` with the keystream that starts at the counter after J0
+    fn apply_keystream(&self, j0: &[u8; BLOCK_SIZE], buf: &mut [u8]) {
+        let mut counter = u32::from_be_bytes(j0[NONCE_SIZE..].try_into().expect("4 bytes"));
+        let mut blocks = [Block::default(); PAR_BLOCKS];
+        for chunk in buf.chunks_mut(BLOCK_SIZE * PAR_BLOCKS) {
+            let n = chunk.len().div_ceil(BLOCK_SIZE);
+            for block in &mut blocks[..n] {
+                counter = counter.wrapping_add(1);
+                block[..NONCE_SIZE].copy_from_slice(&j0[..NONCE_SIZE]);
+                block[NONCE_SIZE..].copy_from_slice(&counter.to_be_bytes());
+            }
+            self.cipher.encrypt_blocks(&mut blocks[..n]);
+            for (byte, key) in chunk.iter_mut().zip(blocks.iter().flatten()) {
+                *byte ^= key;
+            }
+        }
+    }
+
+    // The untruncated 16-byte tag
+    fn tag(&self, j0: &[u8; BLOCK_SIZE], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+        let mut ghash = GHash::new(&self.ghash_key);
+        ghash.update_padded(aad);
+        ghash.update_padded(ciphertext);
+        let mut lengths = Block::default();
+        lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_be_bytes());
+        lengths[8..].copy_from_slice(&(ciphertext.len() as u64 * 8).to_be_bytes());
+        ghash.update(&[lengths]);
+
+        let mut tag = *j0;
+        self.cipher.encrypt_block(&mut tag);
+        for (byte, hash) in tag.iter_mut().zip(ghash.finalize()) {
+            *byte ^= hash;
+        }
+        tag
+    }
+}
+
+// Tags of 12 to 16 bytes are always allowed. NIST SP 800-38D only permits 4-
+// and 8-byte tags in protocols that limit the number of forgery attempts, so
+// those need `allow_short_tag`.
+fn check_tag_len(tag_len: usize, allow_short_tag: bool) -> Result<(), String> {
+    match tag_len {
+        12..=16 => Ok(()),
+        4 | 8 if allow_short_tag => Ok(()),
+        4 | 8 => Err("4- and 8-byte tags require allow_short_tag".to_string()),
+        _ => Err("Tag length must be 12 to 16 bytes, or 4 or 8 bytes".to_string()),
+    }
+}
+
+fn encrypt(
+    plaintext: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag_len: usize,
+) -> Result<Vec<u8>, String> {
+    let gcm = Gcm::new(key)?;
+    let j0 = gcm.initial_counter(iv)?;
+    if plaintext.len() as u64 > MAX_PLAINTEXT_LEN {
+        return Err("Plaintext is too long for AES-GCM".to_string());
+    }
+
+    let mut out = Vec::with_capacity(plaintext.len() + tag_len);
+    out.extend_from_slice(plaintext);
+    gcm.apply_keystream(&j0, &mut out);
+    let tag = gcm.tag(&j0, aad, &out);
+    out.extend_from_slice(&tag[..tag_len]);
+    Ok(out)
+}
+
+fn decrypt(
+    ciphertext: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag_len: usize,
+) -> Result<Vec<u8>, String> {
+    let gcm = Gcm::new(key)?;
+    let j0 = gcm.initial_counter(iv)?;
+    if ciphertext.len() < tag_len {
+        return Err(format!(
+            "Ciphertext must be at least {} bytes (auth tag)",
+            tag_len
+        ));
+    }
+
+    let (body, tag) = ciphertext.split_at(ciphertext.len() - tag_len);
+    if body.len() as u64 > MAX_PLAINTEXT_LEN {
+        return Err("Ciphertext is too long for AES-GCM".to_string());
+    }
+
+    // The tag is checked in constant time over exactly `tag_len` bytes, so a
+    // tag cut shorter than the caller expects is never accepted
+    let expected_tag = gcm.tag(&j0, aad, body);
+    if !bool::from(tag.ct_eq(&expected_tag[..tag_len])) {
+        return Err("Decryption failed: authentication error".to_string());
+    }
+
+    let mut out = body.to_vec();
+    gcm.apply_keystream(&j0, &mut out);
+    Ok(out)
+}
+
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn aesgcm_encrypt(
     plaintext: &[u8],
@@ -13,33 +174,7 @@ pub fn aesgcm_encrypt(
     iv: &[u8],
     aad: &[u8],
 ) -> Result<Vec<u8>, String> {
-    if iv.len() != 12 {
-        return Err("IV must be exactly 12 bytes".to_string());
-    }
-
-    let nonce = Nonce::from_slice(iv);
-    let payload = Payload {
-        msg: plaintext,
-        aad,
-    };
-
-    match key.len() {
-        16 => {
-            let cipher = Aes128Gcm::new_from_slice(key)
-                .map_err(|_| "Invalid key".to_string())?;
-            cipher
-                .encrypt(nonce, payload)
-                .map_err(|_| "Encryption failed".to_string())
-        }
-        32 => {
-            let cipher = Aes256Gcm::new_from_slice(key)
-                .map_err(|_| "Invalid key".to_string())?;
-            cipher
-                .encrypt(nonce, payload)
-                .map_err(|_| "Encryption failed".to_string())
-        }
-        _ => Err("Key must be 16 or 32 bytes".to_string()),
-    }
+    encrypt(plaintext, key, iv, aad, TAG_SIZE)
 }
 
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
@@ -49,42 +184,44 @@ pub fn aesgcm_decrypt(
     iv: &[u8],
     aad: &[u8],
 ) -> Result<Vec<u8>, String> {
-    if iv.len() != 12 {
-        return Err("IV must be exactly 12 bytes".to_string());
-    }
-    if ciphertext.len() < 16 {
-        return Err("Ciphertext must be at least 16 bytes (auth tag)".to_string());
-    }
-
-    let nonce = Nonce::from_slice(iv);
-    let payload = Payload {
-        msg: ciphertext,
-        aad,
-    };
-
-    match key.len() {
-        16 => {
-            let cipher = Aes128Gcm::new_from_slice(key)
-                .map_err(|_| "Invalid key".to_string())?;
-            cipher
-                .decrypt(nonce, payload)
-                .map_err(|_| "Decryption failed: authentication error".to_string())
-        }
-        32 => {
-            let cipher = Aes256Gcm::new_from_slice(key)
-                .map_err(|_| "Invalid key".to_string())?;
-            cipher
-                .decrypt(nonce, payload)
-                .map_err(|_| "Decryption failed: authentication error".to_string())
-        }
-        _ => Err("Key must be 16 or 32 bytes".to_string()),
-    }
+    decrypt(ciphertext, key, iv, aad, TAG_SIZE)
+}
+
+/// Encrypt with a tag truncated to `tag_len` bytes
+/// `tag_len` is 12 to 16, or 4 or 8 when `allow_short_tag` is set.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aesgcm_encrypt_with_tag_len(
+    plaintext: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag_len: usize,
+    allow_short_tag: bool,
+) -> Result<Vec<u8>, String> {
+    check_tag_len(tag_len, allow_short_tag)?;
+    encrypt(plaintext, key, iv, aad, tag_len)
+}
+
+/// Decrypt a ciphertext whose tag is exactly `tag_len` bytes
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aesgcm_decrypt_with_tag_len(
+    ciphertext: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag_len: usize,
+    allow_short_tag: bool,
+) -> Result<Vec<u8>, String> {
+    check_tag_len(tag_len, allow_short_tag)?;
+    decrypt(ciphertext, key, iv, aad, tag_len)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use hex_literal::hex;
+    use serde::Deserialize;
+    use std::fs;
 
     const NO_AAD: &[u8] = &[];
 
@@ -283,15 +420,16 @@ mod tests {
     #[test]
     fn test_invalid_iv_length() {
         let key = [0x01u8; 32];
-        assert!(aesgcm_encrypt(b"test", &key, &[0u8; 16], NO_AAD).is_err()); // 16, not 12
-        assert!(aesgcm_encrypt(b"test", &key, &[0u8; 8], NO_AAD).is_err()); // 8, not 12
+        assert!(aesgcm_encrypt(b"test", &key, &[], NO_AAD).is_err()); // empty
+        assert!(aesgcm_decrypt(&[0u8; 16], &key, &[], NO_AAD).is_err()); // empty
     }
 
     #[test]
     fn test_invalid_key_length() {
         let iv = [0x01u8; 12];
-        assert!(aesgcm_encrypt(b"test", &[0u8; 24], &iv, NO_AAD).is_err()); // 192-bit not supported
         assert!(aesgcm_encrypt(b"test", &[0u8; 10], &iv, NO_AAD).is_err()); // invalid
+        assert!(aesgcm_encrypt(b"test", &[0u8; 20], &iv, NO_AAD).is_err()); // invalid
+        assert!(aesgcm_decrypt(&[0u8; 32], &[0u8; 10], &iv, NO_AAD).is_err());
     }
 
     #[test]
@@ -410,4 +548,269 @@ mod tests {
         tampered[2000] ^= 0x01;
         assert!(aesgcm_decrypt(&ct, &key, &iv, &tampered).is_err());
     }
+
+    // ===========================================================
+    // AES-192 and full NIST outputs, including tags
+    // ===========================================================
+
+    const GCM_KEY: [u8; 16] = hex!("feffe9928665731c6d6a8f9467308308");
+    const GCM_IV: [u8; 12] = hex!("cafebabefacedbaddecaf888");
+    const GCM_PLAINTEXT: [u8; 60] = hex!(
+        "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72"
+        "1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39"
+    );
+    const GCM_AAD: [u8; 20] = hex!("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+
+    // NIST SP 800-38D Test Case 7: AES-192-GCM, zero-length plaintext
+    #[test]
+    fn test_nist_aes192_gcm_empty_plaintext() {
+        let key = [0u8; 24];
+        let iv = [0u8; 12];
+
+        let ciphertext = aesgcm_encrypt(&[], &key, &iv, NO_AAD).unwrap();
+        assert_eq!(ciphertext, hex!("cd33b28ac773f74ba00ed1f312572435"));
+
+        let decrypted = aesgcm_decrypt(&ciphertext, &key, &iv, NO_AAD).unwrap();
+        assert_eq!(decrypted.len(), 0);
+    }
+
+    // NIST SP 800-38D Test Case 10: AES-192-GCM with AAD
+    #[test]
+    fn test_nist_aes192_gcm_with_aad() {
+        let mut key = [0u8; 24];
+        key[..16].copy_from_slice(&GCM_KEY);
+        key[16..].copy_from_slice(&GCM_KEY[..8]);
+
+        let ciphertext = aesgcm_encrypt(&GCM_PLAINTEXT, &key, &GCM_IV, &GCM_AAD).unwrap();
+        assert_eq!(
+            ciphertext,
+            hex!(
+                "3980ca0b3c00e841eb06fac4872a2757859e1ceaa6efd984628593b40ca1e19c"
+                "7d773d00c144c525ac619d18c84a3f4718e2448b2fe324d9ccda2710"
+                "2519498e80f1478f37ba55bd6d27618c"
+            )
+        );
+
+        let decrypted = aesgcm_decrypt(&ciphertext, &key, &GCM_IV, &GCM_AAD).unwrap();
+        assert_eq!(decrypted, GCM_PLAINTEXT);
+    }
+
+    // NIST SP 800-38D Test Cases 2, 8 and 14: one zero block under a zero
+    // key of each size
+    #[test]
+    fn test_nist_zero_block_tags() {
+        let cases: [(usize, [u8; 32]); 3] = [
+            (
+                16,
+                hex!("0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf"),
+            ),
+            (
+                24,
+                hex!("98e7247c07f0fe411c267e4384b0f6002ff58d80033927ab8ef4d4587514f0fb"),
+            ),
+            (
+                32,
+                hex!("cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919"),
+            ),
+        ];
+        for (key_len, expected) in cases {
+            let key = vec![0u8; key_len];
+            let ciphertext = aesgcm_encrypt(&[0u8; 16], &key, &[0u8; 12], NO_AAD).unwrap();
+            assert_eq!(ciphertext, expected, "{}-byte key", key_len);
+        }
+    }
+
+    // NIST SP 800-38D Test Cases 4 and 16: tags with AAD
+    #[test]
+    fn test_nist_tags_with_aad() {
+        let mut key_256 = [0u8; 32];
+        key_256[..16].copy_from_slice(&GCM_KEY);
+        key_256[16..].copy_from_slice(&GCM_KEY);
+        let cases: [(&[u8], [u8; 16]); 2] = [
+            (&GCM_KEY, hex!("5bc94fbc3221a5db94fae95ae7121a47")),
+            (&key_256, hex!("76fc6ece0f4e1768cddf8853bb2d551b")),
+        ];
+        for (key, tag) in cases {
+            let ciphertext = aesgcm_encrypt(&GCM_PLAINTEXT, key, &GCM_IV, &GCM_AAD).unwrap();
+            assert_eq!(ciphertext[GCM_PLAINTEXT.len()..], tag);
+        }
+    }
+
+    #[test]
+    fn test_various_sizes_all_key_lengths() {
+        // Lengths around the eight-block keystream batches
+        for key_len in [16, 24, 32] {
+            let key = vec![0x42u8; key_len];
+            for size in [0, 1, 16, 127, 128, 129, 1000] {
+                let plaintext = vec![0x41u8; size];
+                let ciphertext = aesgcm_encrypt(&plaintext, &key, &GCM_IV, &GCM_AAD).unwrap();
+                let decrypted = aesgcm_decrypt(&ciphertext, &key, &GCM_IV, &GCM_AAD).unwrap();
+                assert_eq!(decrypted, plaintext);
+            }
+        }
+    }
+
+    // ===========================================================
+    // Tag lengths
+    // ===========================================================
+
+    #[test]
+    fn test_truncated_tags_are_prefixes() {
+        let full = aesgcm_encrypt(&GCM_PLAINTEXT, &GCM_KEY, &GCM_IV, &GCM_AAD).unwrap();
+        let (body, full_tag) = full.split_at(GCM_PLAINTEXT.len());
+
+        for tag_len in [4, 8, 12, 13, 14, 15, 16] {
+            let ciphertext = aesgcm_encrypt_with_tag_len(
+                &GCM_PLAINTEXT,
+                &GCM_KEY,
+                &GCM_IV,
+                &GCM_AAD,
+                tag_len,
+                true,
+            )
+            .unwrap();
+            assert_eq!(&ciphertext[..body.len()], body);
+            assert_eq!(&ciphertext[body.len()..], &full_tag[..tag_len]);
+
+            let decrypted = aesgcm_decrypt_with_tag_len(
+                &ciphertext,
+                &GCM_KEY,
+                &GCM_IV,
+                &GCM_AAD,
+                tag_len,
+                true,
+            )
+            .unwrap();
+            assert_eq!(decrypted, GCM_PLAINTEXT);
+        }
+    }
+
+    #[test]
+    fn test_tag_length_is_bound_into_decrypt() {
+        let full = aesgcm_encrypt(b"tag length", &GCM_KEY, &GCM_IV, NO_AAD).unwrap();
+
+        // A full tag cut down to any allowed length is rejected by the
+        // default decrypt
+        for tag_len in [4, 8, 12, 15] {
+            let truncated = &full[..full.len() - 16 + tag_len];
+            assert!(aesgcm_decrypt(truncated, &GCM_KEY, &GCM_IV, NO_AAD).is_err());
+        }
+
+        // A 12-byte tag does not verify as a 13- or 8-byte one
+        let short =
+            aesgcm_encrypt_with_tag_len(b"tag length", &GCM_KEY, &GCM_IV, NO_AAD, 12, false)
+                .unwrap();
+        assert!(aesgcm_decrypt_with_tag_len(&short, &GCM_KEY, &GCM_IV, NO_AAD, 13, false).is_err());
+        assert!(aesgcm_decrypt_with_tag_len(&short, &GCM_KEY, &GCM_IV, NO_AAD, 8, true).is_err());
+        assert!(aesgcm_decrypt_with_tag_len(&short, &GCM_KEY, &GCM_IV, NO_AAD, 12, false).is_ok());
+
+        // Tampering with a short tag is detected
+        let mut tampered =
+            aesgcm_encrypt_with_tag_len(b"tag length", &GCM_KEY, &GCM_IV, NO_AAD, 4, true).unwrap();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        assert_eq!(
+            aesgcm_decrypt_with_tag_len(&tampered, &GCM_KEY, &GCM_IV, NO_AAD, 4, true).unwrap_err(),
+            "Decryption failed: authentication error"
+        );
+    }
+
+    #[test]
+    fn test_short_tags_need_opt_in() {
+        for tag_len in [4, 8] {
+            assert!(aesgcm_encrypt_with_tag_len(
+                b"test", &GCM_KEY, &GCM_IV, NO_AAD, tag_len, false
+            )
+            .is_err());
+            assert!(aesgcm_decrypt_with_tag_len(
+                &[0u8; 20], &GCM_KEY, &GCM_IV, NO_AAD, tag_len, false
+            )
+            .is_err());
+        }
+        for tag_len in [0, 1, 5, 11, 17, 32] {
+            assert!(
+                aesgcm_encrypt_with_tag_len(b"test", &GCM_KEY, &GCM_IV, NO_AAD, tag_len, true)
+                    .is_err(),
+                "tag length {}",
+                tag_len
+            );
+        }
+        assert!(
+            aesgcm_decrypt_with_tag_len(&[0u8; 11], &GCM_KEY, &GCM_IV, NO_AAD, 12, false).is_err()
+        );
+    }
+
+    // ===========================================================
+    // IVs other than 12 bytes
+    // ===========================================================
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        name: String,
+        key: String,
+        iv: String,
+        pt: String,
+        aad: String,
+        ct: String,
+        tag: String,
+    }
+
+    #[test]
+    fn test_ext_iv_vectors() {
+        let data = fs::read_to_string("vectors/vectors-gcm-iv.json")
+            .expect("Unable to read test vectors file");
+        let vectors: Vec<TestVector> =
+            serde_json::from_str(&data).expect("Error parsing JSON test vectors");
+
+        for vector in vectors {
+            let key = hex::decode(&vector.key).unwrap();
+            let iv = hex::decode(&vector.iv).unwrap();
+            let plaintext = hex::decode(&vector.pt).unwrap();
+            let aad = hex::decode(&vector.aad).unwrap();
+            let mut expected = hex::decode(&vector.ct).unwrap();
+            expected.extend(hex::decode(&vector.tag).unwrap());
+
+            let ciphertext = aesgcm_encrypt(&plaintext, &key, &iv, &aad).unwrap();
+            assert_eq!(ciphertext, expected, "{}", vector.name);
+            let decrypted = aesgcm_decrypt(&ciphertext, &key, &iv, &aad).unwrap();
+            assert_eq!(decrypted, plaintext, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_ext_iv_round_trip_and_binding() {
+        let key = [0x42u8; 32];
+        let plaintext = vec![0x41u8; 200];
+        for iv_len in [1, 8, 11, 13, 16, 64, 200] {
+            let iv: Vec<u8> = (0..iv_len).map(|i| i as u8).collect();
+            let ciphertext = aesgcm_encrypt(&plaintext, &key, &iv, NO_AAD).unwrap();
+            let decrypted = aesgcm_decrypt(&ciphertext, &key, &iv, NO_AAD).unwrap();
+            assert_eq!(decrypted, plaintext, "{}-byte IV", iv_len);
+
+            // The IV length is part of the hash, so a zero-extended IV differs
+            let mut extended = iv.clone();
+            extended.push(0);
+            assert!(aesgcm_decrypt(&ciphertext, &key, &extended, NO_AAD).is_err());
+        }
+
+        // A 16-byte IV is hashed, not used as the counter block
+        let iv_12 = hex!("cafebabefacedbaddecaf888");
+        let j0 = hex!("cafebabefacedbaddecaf88800000001");
+        assert_ne!(
+            aesgcm_encrypt(b"test", &key, &iv_12, NO_AAD).unwrap(),
+            aesgcm_encrypt(b"test", &key, &j0, NO_AAD).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ext_iv_with_tag_len() {
+        let key = [0x42u8; 16];
+        let iv = [0x07u8; 16];
+        let ciphertext =
+            aesgcm_encrypt_with_tag_len(b"legacy", &key, &iv, NO_AAD, 12, false).unwrap();
+        assert_eq!(ciphertext.len(), 6 + 12);
+        let decrypted =
+            aesgcm_decrypt_with_tag_len(&ciphertext, &key, &iv, NO_AAD, 12, false).unwrap();
+        assert_eq!(decrypted, b"legacy");
+    }
 }
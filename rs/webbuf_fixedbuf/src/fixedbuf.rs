@@ -0,0 +1,200 @@
+// Fixed-size byte buffers, matching FixedBuf in @webbuf/fixedbuf
+
+use std::fmt;
+use subtle::ConstantTimeEq;
+use webbuf::{decode_base64, decode_hex, encode_base64, encode_hex};
+use zeroize::Zeroize;
+
+/// A byte buffer whose length is part of its type
+/// Equality is constant-time, so comparing MACs, keys or hashes with `==`
+/// does not leak where the first difference is.
+#[derive(Clone)]
+pub struct FixedBuf<const N: usize> {
+    buf: [u8; N],
+}
+
+impl<const N: usize> FixedBuf<N> {
+    pub fn from_array(buf: [u8; N]) -> FixedBuf<N> {
+        FixedBuf { buf }
+    }
+
+    pub fn from_buf(buf: &[u8]) -> Result<FixedBuf<N>, String> {
+        let buf: [u8; N] = buf
+            .try_into()
+            .map_err(|_| "invalid size error".to_string())?;
+        Ok(FixedBuf { buf })
+    }
+
+    pub fn alloc(fill: u8) -> FixedBuf<N> {
+        FixedBuf { buf: [fill; N] }
+    }
+
+    pub fn from_hex(hex: &str) -> Result<FixedBuf<N>, String> {
+        FixedBuf::from_buf(&decode_hex(hex)?)
+    }
+
+    pub fn to_hex(&self) -> String {
+        encode_hex(&self.buf)
+    }
+
+    pub fn from_base64(base64: &str) -> Result<FixedBuf<N>, String> {
+        let buf = decode_base64(base64).map_err(|_| "invalid encoding".to_string())?;
+        FixedBuf::from_buf(&buf).map_err(|_| "invalid encoding".to_string())
+    }
+
+    pub fn to_base64(&self) -> String {
+        encode_base64(&self.buf)
+    }
+
+    /// Fill a buffer from the operating system's random number generator
+    pub fn from_random() -> Result<FixedBuf<N>, String> {
+        let mut buf = [0u8; N];
+        getrandom::getrandom(&mut buf).map_err(|e| format!("random generation failed: {}", e))?;
+        Ok(FixedBuf { buf })
+    }
+
+    pub fn buf(&self) -> &[u8; N] {
+        &self.buf
+    }
+
+    pub fn into_array(self) -> [u8; N] {
+        self.buf
+    }
+
+    pub fn to_reverse(&self) -> FixedBuf<N> {
+        let mut buf = self.buf;
+        buf.reverse();
+        FixedBuf { buf }
+    }
+
+    /// Constant-time comparison
+    pub fn ct_eq(&self, other: &FixedBuf<N>) -> bool {
+        self.buf.ct_eq(&other.buf).into()
+    }
+
+    /// Overwrite the buffer with zeros in a way the compiler will not
+    /// optimize away. Call this before dropping keys and other secrets.
+    pub fn wipe(&mut self) {
+        self.buf.zeroize();
+    }
+}
+
+impl<const N: usize> Default for FixedBuf<N> {
+    fn default() -> FixedBuf<N> {
+        FixedBuf::alloc(0)
+    }
+}
+
+impl<const N: usize> PartialEq for FixedBuf<N> {
+    fn eq(&self, other: &FixedBuf<N>) -> bool {
+        self.ct_eq(other)
+    }
+}
+
+impl<const N: usize> Eq for FixedBuf<N> {}
+
+// The bytes are often keys or other secrets, so Debug shows only the length.
+// Use to_hex to print the contents deliberately.
+impl<const N: usize> fmt::Debug for FixedBuf<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FixedBuf<{}>(..)", N)
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for FixedBuf<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for FixedBuf<N> {
+    fn from(buf: [u8; N]) -> FixedBuf<N> {
+        FixedBuf::from_array(buf)
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for FixedBuf<N> {
+    type Error = String;
+
+    fn try_from(buf: &[u8]) -> Result<FixedBuf<N>, String> {
+        FixedBuf::from_buf(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_buf_enforces_size() {
+        assert!(FixedBuf::<4>::from_buf(&[1, 2, 3, 4]).is_ok());
+        assert_eq!(
+            FixedBuf::<4>::from_buf(&[1, 2, 3]).unwrap_err(),
+            "invalid size error"
+        );
+        assert_eq!(
+            FixedBuf::<4>::from_buf(&[1, 2, 3, 4, 5]).unwrap_err(),
+            "invalid size error"
+        );
+        assert!(FixedBuf::<0>::from_buf(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let buf = FixedBuf::<4>::from_hex("deadbeef").unwrap();
+        assert_eq!(buf.buf(), &[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(buf.to_hex(), "deadbeef");
+        assert_eq!(
+            FixedBuf::<4>::from_hex("deadbe").unwrap_err(),
+            "invalid size error"
+        );
+        assert_eq!(FixedBuf::<4>::from_hex("zz").unwrap_err(), "invalid hex");
+    }
+
+    #[test]
+    fn test_base64_roundtrip() {
+        let buf = FixedBuf::<5>::from_base64("SGVsbG8=").unwrap();
+        assert_eq!(buf.buf(), b"Hello");
+        assert_eq!(buf.to_base64(), "SGVsbG8=");
+        assert_eq!(
+            FixedBuf::<4>::from_base64("SGVsbG8=").unwrap_err(),
+            "invalid encoding"
+        );
+        assert_eq!(
+            FixedBuf::<5>::from_base64("SGVsbG8").unwrap_err(),
+            "invalid encoding"
+        );
+    }
+
+    #[test]
+    fn test_from_random() {
+        let a = FixedBuf::<32>::from_random().unwrap();
+        let b = FixedBuf::<32>::from_random().unwrap();
+        assert_ne!(a, b);
+        assert_ne!(a, FixedBuf::default());
+    }
+
+    #[test]
+    fn test_equality() {
+        let a = FixedBuf::from_array([1u8, 2, 3]);
+        assert_eq!(a, a.clone());
+        assert!(a.ct_eq(&FixedBuf::from([1, 2, 3])));
+        assert_ne!(a, FixedBuf::from([1, 2, 4]));
+        assert_ne!(a, FixedBuf::from([0, 2, 3]));
+    }
+
+    #[test]
+    fn test_reverse_and_wipe() {
+        let mut buf = FixedBuf::from_array([1u8, 2, 3]);
+        assert_eq!(buf.to_reverse().into_array(), [3, 2, 1]);
+        assert_eq!(buf.buf(), &[1, 2, 3]);
+        buf.wipe();
+        assert_eq!(buf, FixedBuf::alloc(0));
+    }
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let buf = FixedBuf::from_array([0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(format!("{:?}", buf), "FixedBuf<4>(..)");
+    }
+}
@@ -0,0 +1,5 @@
+mod fixedbuf;
+mod numbers;
+
+pub use fixedbuf::FixedBuf;
+pub use numbers::{U128BE, U128LE, U16BE, U16LE, U256BE, U256LE, U32BE, U32LE, U64BE, U64LE, U8};
@@ -0,0 +1,427 @@
+// Fixed-width unsigned integers, matching @webbuf/numbers
+
+use crate::FixedBuf;
+use std::cmp::Ordering;
+
+/// Same message as the TypeScript number types
+const INVALID_NUMBER: &str = "Invalid number";
+
+fn invalid_number() -> String {
+    INVALID_NUMBER.to_string()
+}
+
+/// 256-bit value backing U256BE and U256LE, as little-endian 64-bit limbs
+/// Its methods mirror the primitive integers so the macro below can treat
+/// every width the same way.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+struct U256([u64; 4]);
+
+impl U256 {
+    fn from_be_bytes(bytes: [u8; 32]) -> U256 {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 24 - 8 * i;
+            *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+        }
+        U256(limbs)
+    }
+
+    fn to_be_bytes(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, limb) in self.0.iter().enumerate() {
+            let start = 24 - 8 * i;
+            bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn overflowing_add(self, other: U256) -> (U256, bool) {
+        let mut limbs = [0u64; 4];
+        let mut carry = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        (U256(limbs), carry)
+    }
+
+    fn overflowing_sub(self, other: U256) -> (U256, bool) {
+        let mut limbs = [0u64; 4];
+        let mut borrow = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (diff, b1) = self.0[i].overflowing_sub(other.0[i]);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+        (U256(limbs), borrow)
+    }
+
+    fn checked_add(self, other: U256) -> Option<U256> {
+        match self.overflowing_add(other) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+
+    fn checked_sub(self, other: U256) -> Option<U256> {
+        match self.overflowing_sub(other) {
+            (diff, false) => Some(diff),
+            (_, true) => None,
+        }
+    }
+
+    fn checked_mul(self, other: U256) -> Option<U256> {
+        // Schoolbook multiplication into 512 bits; any high limb means overflow
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = self.0[i] as u128 * other.0[j] as u128 + product[i + j] as u128 + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+        if product[4..].iter().any(|&limb| limb != 0) {
+            return None;
+        }
+        Some(U256(product[..4].try_into().unwrap()))
+    }
+
+    fn checked_div(self, other: U256) -> Option<U256> {
+        if other == U256::default() {
+            return None;
+        }
+        // Binary long division, most significant bit first
+        let mut quotient = U256::default();
+        let mut remainder = U256::default();
+        for bit in (0..256).rev() {
+            let (shifted, carry) = remainder.overflowing_add(remainder);
+            remainder = shifted;
+            remainder.0[0] |= (self.0[bit / 64] >> (bit % 64)) & 1;
+            if carry || remainder >= other {
+                remainder = remainder.overflowing_sub(other).0;
+                quotient.0[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        Some(quotient)
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &U256) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &U256) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(n: u128) -> U256 {
+        U256([n as u64, (n >> 64) as u64, 0, 0])
+    }
+}
+
+macro_rules! fixed_num {
+    ($(#[$doc:meta])* $name:ident, $size:expr, $value:ty, $little_endian:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Debug, Default, PartialEq, Eq)]
+        pub struct $name {
+            buf: FixedBuf<$size>,
+        }
+
+        impl $name {
+            fn from_value(value: $value) -> $name {
+                let mut bytes = value.to_be_bytes();
+                if $little_endian {
+                    bytes.reverse();
+                }
+                $name {
+                    buf: FixedBuf::from_array(bytes),
+                }
+            }
+
+            fn value(&self) -> $value {
+                let mut bytes = *self.buf.buf();
+                if $little_endian {
+                    bytes.reverse();
+                }
+                <$value>::from_be_bytes(bytes)
+            }
+
+            /// Range-checked construction, the counterpart of `fromBn`
+            pub fn from_u128(n: u128) -> Result<$name, String> {
+                let value = <$value>::try_from(n).map_err(|_| invalid_number())?;
+                Ok($name::from_value(value))
+            }
+
+            /// The bytes of the number in this type's byte order
+            pub fn buf(&self) -> &FixedBuf<$size> {
+                &self.buf
+            }
+
+            pub fn from_be_buf(buf: FixedBuf<$size>) -> $name {
+                $name::from_value(<$value>::from_be_bytes(buf.into_array()))
+            }
+
+            pub fn from_le_buf(buf: FixedBuf<$size>) -> $name {
+                $name::from_be_buf(buf.to_reverse())
+            }
+
+            pub fn to_be_buf(&self) -> FixedBuf<$size> {
+                FixedBuf::from_array(self.value().to_be_bytes())
+            }
+
+            pub fn to_le_buf(&self) -> FixedBuf<$size> {
+                self.to_be_buf().to_reverse()
+            }
+
+            /// Parse big-endian hex, whatever the byte order of the type
+            pub fn from_hex(hex: &str) -> Result<$name, String> {
+                Ok($name::from_be_buf(FixedBuf::from_hex(hex)?))
+            }
+
+            /// Big-endian hex, whatever the byte order of the type
+            pub fn to_hex(&self) -> String {
+                self.to_be_buf().to_hex()
+            }
+
+            pub fn checked_add(&self, other: &$name) -> Result<$name, String> {
+                let value = self.value().checked_add(other.value());
+                value.map($name::from_value).ok_or_else(invalid_number)
+            }
+
+            pub fn checked_sub(&self, other: &$name) -> Result<$name, String> {
+                let value = self.value().checked_sub(other.value());
+                value.map($name::from_value).ok_or_else(invalid_number)
+            }
+
+            pub fn checked_mul(&self, other: &$name) -> Result<$name, String> {
+                let value = self.value().checked_mul(other.value());
+                value.map($name::from_value).ok_or_else(invalid_number)
+            }
+
+            /// Integer division, rounding down
+            pub fn checked_div(&self, other: &$name) -> Result<$name, String> {
+                let value = self.value().checked_div(other.value());
+                value
+                    .map($name::from_value)
+                    .ok_or_else(|| "division by zero".to_string())
+            }
+        }
+    };
+}
+
+/// Constructors and accessors for the widths that fit a primitive integer
+macro_rules! primitive_num {
+    ($name:ident, $value:ty) => {
+        impl $name {
+            pub fn new(n: $value) -> $name {
+                $name::from_value(n)
+            }
+
+            pub fn n(&self) -> $value {
+                self.value()
+            }
+        }
+    };
+}
+
+fixed_num!(
+    /// 8-bit unsigned integer
+    U8, 1, u8, false
+);
+fixed_num!(
+    /// 16-bit unsigned integer stored big-endian
+    U16BE, 2, u16, false
+);
+fixed_num!(
+    /// 16-bit unsigned integer stored little-endian
+    U16LE, 2, u16, true
+);
+fixed_num!(
+    /// 32-bit unsigned integer stored big-endian
+    U32BE, 4, u32, false
+);
+fixed_num!(
+    /// 32-bit unsigned integer stored little-endian
+    U32LE, 4, u32, true
+);
+fixed_num!(
+    /// 64-bit unsigned integer stored big-endian
+    U64BE, 8, u64, false
+);
+fixed_num!(
+    /// 64-bit unsigned integer stored little-endian
+    U64LE, 8, u64, true
+);
+fixed_num!(
+    /// 128-bit unsigned integer stored big-endian
+    U128BE, 16, u128, false
+);
+fixed_num!(
+    /// 128-bit unsigned integer stored little-endian
+    U128LE, 16, u128, true
+);
+fixed_num!(
+    /// 256-bit unsigned integer stored big-endian
+    U256BE, 32, U256, false
+);
+fixed_num!(
+    /// 256-bit unsigned integer stored little-endian
+    U256LE, 32, U256, true
+);
+
+primitive_num!(U8, u8);
+primitive_num!(U16BE, u16);
+primitive_num!(U16LE, u16);
+primitive_num!(U32BE, u32);
+primitive_num!(U32LE, u32);
+primitive_num!(U64BE, u64);
+primitive_num!(U64LE, u64);
+primitive_num!(U128BE, u128);
+primitive_num!(U128LE, u128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const U256_MAX_HEX: &str = "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+
+    #[test]
+    fn test_byte_order() {
+        assert_eq!(U16BE::new(0x0102).buf().to_hex(), "0102");
+        assert_eq!(U16LE::new(0x0102).buf().to_hex(), "0201");
+        assert_eq!(U32BE::new(0x01020304).to_le_buf().to_hex(), "04030201");
+        assert_eq!(U32LE::new(0x01020304).buf().to_hex(), "04030201");
+        assert_eq!(U32LE::new(0x01020304).to_hex(), "01020304");
+        assert_eq!(
+            U64LE::new(0x0102030405060708).to_le_buf().to_hex(),
+            "0807060504030201"
+        );
+        assert_eq!(
+            U128BE::new(0x0102030405060708090a0b0c0d0e0f10)
+                .to_le_buf()
+                .to_hex(),
+            "100f0e0d0c0b0a090807060504030201"
+        );
+        let le = U32LE::from_be_buf(FixedBuf::from_array([1, 2, 3, 4]));
+        assert_eq!(le.n(), 0x01020304);
+        assert_eq!(U32BE::from_le_buf(le.buf().clone()).n(), 0x01020304);
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(U8::from_hex("ff").unwrap().n(), 0xff);
+        assert_eq!(U16LE::from_hex("0102").unwrap().n(), 0x0102);
+        assert_eq!(U64BE::new(u64::MAX).to_hex(), "ffffffffffffffff");
+        assert_eq!(
+            U256LE::from_hex(U256_MAX_HEX).unwrap().to_hex(),
+            U256_MAX_HEX
+        );
+        assert!(U32BE::from_hex("010203").is_err());
+    }
+
+    #[test]
+    fn test_from_u128_range_check() {
+        assert_eq!(U8::from_u128(0xff).unwrap().n(), 0xff);
+        assert_eq!(U8::from_u128(0x100).unwrap_err(), "Invalid number");
+        assert!(U16BE::from_u128(0x10000).is_err());
+        assert!(U16LE::from_u128(0x10000).is_err());
+        assert!(U64BE::from_u128(1 << 64).is_err());
+        assert_eq!(U128LE::from_u128(u128::MAX).unwrap().n(), u128::MAX);
+        assert_eq!(
+            U256BE::from_u128(u128::MAX).unwrap().to_hex(),
+            "00000000000000000000000000000000ffffffffffffffffffffffffffffffff"
+        );
+    }
+
+    #[test]
+    fn test_u32_overflow_check() {
+        // The audit found the TS check compared against U64 max
+        assert_eq!(U32BE::from_u128(0xffffffff).unwrap().n(), u32::MAX);
+        assert_eq!(U32LE::from_u128(0xffffffff).unwrap().n(), u32::MAX);
+        assert!(U32BE::from_u128(0x100000000).is_err());
+        assert!(U32LE::from_u128(0x100000000).is_err());
+        assert!(U32BE::from_u128(u64::MAX as u128).is_err());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = U16BE::new(20);
+        let b = U16BE::new(10);
+        assert_eq!(a.checked_add(&b).unwrap().n(), 30);
+        assert_eq!(a.checked_sub(&b).unwrap().n(), 10);
+        assert_eq!(a.checked_mul(&b).unwrap().n(), 200);
+        assert_eq!(a.checked_div(&b).unwrap().n(), 2);
+        assert_eq!(U8::new(7).checked_div(&U8::new(2)).unwrap().n(), 3);
+
+        assert_eq!(b.checked_sub(&a).unwrap_err(), "Invalid number");
+        let max = U32LE::new(u32::MAX);
+        assert_eq!(
+            max.checked_add(&U32LE::new(1)).unwrap_err(),
+            "Invalid number"
+        );
+        assert!(max.checked_mul(&U32LE::new(2)).is_err());
+        assert_eq!(
+            max.checked_div(&U32LE::new(0)).unwrap_err(),
+            "division by zero"
+        );
+    }
+
+    #[test]
+    fn test_u256_arithmetic() {
+        let small = |n| U256BE::from_u128(n).unwrap();
+        assert_eq!(small(10).checked_add(&small(20)).unwrap(), small(30));
+        assert_eq!(small(20).checked_sub(&small(10)).unwrap(), small(10));
+        assert_eq!(small(10).checked_mul(&small(20)).unwrap(), small(200));
+        assert_eq!(small(20).checked_div(&small(10)).unwrap(), small(2));
+
+        let a =
+            U256BE::from_hex("555555555555555555555555555555555555555555555555555555555555858e")
+                .unwrap();
+        let b = small(0x1234567890abcdef1234567890abcdef);
+        assert_eq!(
+            a.checked_div(&b).unwrap().to_hex(),
+            "00000000000000000000000000000004b00000029793004359f139fc4982a89b"
+        );
+        let x = small(0x0123456789abcdef0123456789abcdef);
+        let y = small(0xfedcba9876543210fedcba98);
+        assert_eq!(
+            x.checked_mul(&y).unwrap().to_hex(),
+            "000000000121fa00ad77d742247acc913fca99aad05ebe789252c268ad05ebe8"
+        );
+
+        // Carries and borrows across limbs
+        let max = U256LE::from_hex(U256_MAX_HEX).unwrap();
+        let one = U256LE::from_u128(1).unwrap();
+        let two_pow_128 = U256LE::from_u128(u128::MAX)
+            .unwrap()
+            .checked_add(&one)
+            .unwrap();
+        assert_eq!(max.checked_sub(&max).unwrap(), U256LE::default());
+        assert_eq!(max.checked_div(&max).unwrap(), one);
+        assert_eq!(max.checked_div(&one).unwrap(), max);
+        assert_eq!(
+            max.checked_sub(&one).unwrap().checked_add(&one).unwrap(),
+            max
+        );
+        assert_eq!(max.checked_add(&one).unwrap_err(), "Invalid number");
+        assert_eq!(one.checked_sub(&max).unwrap_err(), "Invalid number");
+        assert_eq!(
+            two_pow_128.checked_mul(&two_pow_128).unwrap_err(),
+            "Invalid number"
+        );
+        assert_eq!(
+            max.checked_div(&U256LE::default()).unwrap_err(),
+            "division by zero"
+        );
+    }
+}
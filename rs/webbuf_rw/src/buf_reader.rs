@@ -0,0 +1,301 @@
+// Sequential reads from a byte slice, matching BufReader in @webbuf/rw
+
+use std::fmt;
+
+/// Why a read failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BufReaderError {
+    /// The read would go past the end of the buffer
+    NotEnoughBytes,
+    /// A VarInt used a longer form than its value needs
+    NonMinimalEncoding,
+}
+
+impl fmt::Display for BufReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Same messages as the TypeScript BufReader
+        match self {
+            BufReaderError::NotEnoughBytes => write!(f, "not enough bytes in the buffer to read"),
+            BufReaderError::NonMinimalEncoding => write!(f, "non-minimal encoding"),
+        }
+    }
+}
+
+impl std::error::Error for BufReaderError {}
+
+impl From<BufReaderError> for String {
+    fn from(err: BufReaderError) -> String {
+        err.to_string()
+    }
+}
+
+/// Reads integers, fixed-size arrays and VarInts from a byte slice
+/// A failed read of a fixed size leaves the position unchanged.
+#[derive(Clone, Debug)]
+pub struct BufReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BufReader<'a> {
+    pub fn new(buf: &'a [u8]) -> BufReader<'a> {
+        BufReader { buf, pos: 0 }
+    }
+
+    /// Number of bytes read so far
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    pub fn eof(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    pub fn read(&mut self, len: usize) -> Result<&'a [u8], BufReaderError> {
+        if len > self.remaining() {
+            return Err(BufReaderError::NotEnoughBytes);
+        }
+        let buf = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(buf)
+    }
+
+    pub fn read_fixed<const N: usize>(&mut self) -> Result<[u8; N], BufReaderError> {
+        let buf = self.read(N)?;
+        Ok(buf.try_into().expect("read returns exactly N bytes"))
+    }
+
+    pub fn read_remainder(&mut self) -> &'a [u8] {
+        let buf = &self.buf[self.pos..];
+        self.pos = self.buf.len();
+        buf
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, BufReaderError> {
+        Ok(self.read_fixed::<1>()?[0])
+    }
+
+    pub fn read_u16_be(&mut self) -> Result<u16, BufReaderError> {
+        Ok(u16::from_be_bytes(self.read_fixed()?))
+    }
+
+    pub fn read_u16_le(&mut self) -> Result<u16, BufReaderError> {
+        Ok(u16::from_le_bytes(self.read_fixed()?))
+    }
+
+    pub fn read_u32_be(&mut self) -> Result<u32, BufReaderError> {
+        Ok(u32::from_be_bytes(self.read_fixed()?))
+    }
+
+    pub fn read_u32_le(&mut self) -> Result<u32, BufReaderError> {
+        Ok(u32::from_le_bytes(self.read_fixed()?))
+    }
+
+    pub fn read_u64_be(&mut self) -> Result<u64, BufReaderError> {
+        Ok(u64::from_be_bytes(self.read_fixed()?))
+    }
+
+    pub fn read_u64_le(&mut self) -> Result<u64, BufReaderError> {
+        Ok(u64::from_le_bytes(self.read_fixed()?))
+    }
+
+    pub fn read_u128_be(&mut self) -> Result<u128, BufReaderError> {
+        Ok(u128::from_be_bytes(self.read_fixed()?))
+    }
+
+    pub fn read_u128_le(&mut self) -> Result<u128, BufReaderError> {
+        Ok(u128::from_le_bytes(self.read_fixed()?))
+    }
+
+    /// Read a 256-bit big-endian integer as its 32 big-endian bytes
+    pub fn read_u256_be(&mut self) -> Result<[u8; 32], BufReaderError> {
+        self.read_fixed()
+    }
+
+    /// Read a 256-bit little-endian integer as its 32 big-endian bytes
+    pub fn read_u256_le(&mut self) -> Result<[u8; 32], BufReaderError> {
+        let mut buf: [u8; 32] = self.read_fixed()?;
+        buf.reverse();
+        Ok(buf)
+    }
+
+    /// Read the raw bytes of a VarInt, prefix included
+    /// The prefix is 0xfd, 0xfe or 0xff for a 2, 4 or 8-byte big-endian value;
+    /// any other first byte is the value itself. Values that fit a shorter
+    /// form are rejected.
+    pub fn read_var_int_buf(&mut self) -> Result<&'a [u8], BufReaderError> {
+        let start = self.pos;
+        let first = self.read_u8()?;
+        let minimal = match first {
+            0xfd => self.read_u16_be()? >= 0xfd,
+            0xfe => self.read_u32_be()? >= 0x10000,
+            0xff => self.read_u64_be()? >= 0x100000000,
+            _ => true,
+        };
+        if !minimal {
+            return Err(BufReaderError::NonMinimalEncoding);
+        }
+        Ok(&self.buf[start..self.pos])
+    }
+
+    /// Read a minimally encoded VarInt
+    pub fn read_var_int(&mut self) -> Result<u64, BufReaderError> {
+        let mut reader = BufReader::new(self.read_var_int_buf()?);
+        let value = match reader.read_u8()? {
+            0xfd => reader.read_u16_be()? as u64,
+            0xfe => reader.read_u32_be()? as u64,
+            0xff => reader.read_u64_be()?,
+            first => first as u64,
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BufWriter;
+    use hex::decode;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+    use std::fs;
+
+    #[derive(Deserialize)]
+    struct ErrorVector {
+        hex: String,
+        len: Option<usize>,
+        error: String,
+    }
+
+    #[derive(Deserialize)]
+    struct ErrorVectors {
+        errors: Vec<ErrorVector>,
+    }
+
+    // Vectors shared with the TypeScript package
+    fn load_vectors() -> HashMap<String, ErrorVectors> {
+        let data = fs::read_to_string("../../ts/npm-webbuf-rw/vectors/buf-reader.json")
+            .expect("Unable to read test vectors file");
+        serde_json::from_str(&data).expect("Error parsing JSON test vectors")
+    }
+
+    #[test]
+    fn test_ts_vectors() {
+        let vectors = load_vectors();
+        assert_eq!(vectors.len(), 7);
+        for (name, vectors) in &vectors {
+            for vector in &vectors.errors {
+                let buf = decode(&vector.hex).unwrap();
+                let mut reader = BufReader::new(&buf);
+                let err = match name.as_str() {
+                    "read" => reader.read(vector.len.unwrap()).map(|_| ()),
+                    "read_u8" => reader.read_u8().map(|_| ()),
+                    "read_u16_be" => reader.read_u16_be().map(|_| ()),
+                    "read_u32_be" => reader.read_u32_be().map(|_| ()),
+                    "read_u64_be" => reader.read_u64_be().map(|_| ()),
+                    "read_var_int_buf" => reader.read_var_int_buf().map(|_| ()),
+                    "read_var_int" => reader.read_var_int().map(|_| ()),
+                    _ => panic!("unknown vector group {}", name),
+                }
+                .unwrap_err();
+                assert_eq!(err.to_string(), vector.error, "{} {}", name, vector.hex);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        let buf = decode("0102030405").unwrap();
+        let mut reader = BufReader::new(&buf);
+        assert_eq!(reader.read(2).unwrap(), [1, 2]);
+        assert_eq!(reader.read_fixed::<2>().unwrap(), [3, 4]);
+        assert_eq!(reader.read(2), Err(BufReaderError::NotEnoughBytes));
+        assert_eq!(reader.pos(), 4);
+        assert_eq!(reader.read_remainder(), [5]);
+        assert!(reader.eof());
+        assert!(reader.read_remainder().is_empty());
+    }
+
+    #[test]
+    fn test_read_integers() {
+        let buf = decode("0102030405060708090a0b0c0d0e0f10").unwrap();
+        let mut reader = BufReader::new(&buf);
+        assert_eq!(reader.read_u8().unwrap(), 0x01);
+        assert_eq!(reader.read_u16_be().unwrap(), 0x0203);
+        assert_eq!(reader.read_u16_le().unwrap(), 0x0504);
+        assert_eq!(reader.read_u32_be().unwrap(), 0x06070809);
+        assert_eq!(reader.read_u32_le().unwrap(), 0x0d0c0b0a);
+        assert_eq!(reader.remaining(), 3);
+        assert!(reader.read_u32_be().is_err());
+        assert_eq!(reader.remaining(), 3);
+
+        let mut reader = BufReader::new(&buf);
+        assert_eq!(reader.read_u64_be().unwrap(), 0x0102030405060708);
+        assert_eq!(reader.read_u64_le().unwrap(), 0x100f0e0d0c0b0a09);
+
+        let mut reader = BufReader::new(&buf);
+        assert_eq!(
+            reader.read_u128_be().unwrap(),
+            0x0102030405060708090a0b0c0d0e0f10
+        );
+        let mut reader = BufReader::new(&buf);
+        assert_eq!(
+            reader.read_u128_le().unwrap(),
+            0x100f0e0d0c0b0a090807060504030201
+        );
+    }
+
+    #[test]
+    fn test_read_u256() {
+        let buf: Vec<u8> = (1..=32).collect();
+        assert_eq!(BufReader::new(&buf).read_u256_be().unwrap()[..], buf[..]);
+        let mut reversed = buf.clone();
+        reversed.reverse();
+        assert_eq!(
+            BufReader::new(&buf).read_u256_le().unwrap()[..],
+            reversed[..]
+        );
+        assert_eq!(
+            BufReader::new(&buf[1..]).read_u256_be(),
+            Err(BufReaderError::NotEnoughBytes)
+        );
+    }
+
+    #[test]
+    fn test_read_var_int() {
+        let cases: [(&str, u64); 8] = [
+            ("00", 0),
+            ("01", 1),
+            ("fc", 0xfc),
+            ("fd00fd", 0xfd),
+            ("fdffff", 0xffff),
+            ("fe00010000", 0x10000),
+            ("ff0000000100000000", 0x100000000),
+            ("ffffffffffffffffff", u64::MAX),
+        ];
+        for (hex, value) in cases {
+            let buf = decode(hex).unwrap();
+            assert_eq!(BufReader::new(&buf).read_var_int_buf().unwrap(), buf);
+            let mut reader = BufReader::new(&buf);
+            assert_eq!(reader.read_var_int().unwrap(), value);
+            assert!(reader.eof());
+            assert_eq!(BufWriter::var_int_buf(value), buf);
+        }
+    }
+
+    #[test]
+    fn test_read_var_int_rejects_non_minimal() {
+        for hex in ["fd00fc", "fe0000ffff", "ff00000000ffffffff"] {
+            let buf = decode(hex).unwrap();
+            assert_eq!(
+                BufReader::new(&buf).read_var_int(),
+                Err(BufReaderError::NonMinimalEncoding)
+            );
+        }
+    }
+}
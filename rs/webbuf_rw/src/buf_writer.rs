@@ -0,0 +1,181 @@
+// Sequential writes into a growable buffer, matching BufWriter in @webbuf/rw
+
+/// Appends integers, byte slices and VarInts to a buffer
+#[derive(Clone, Debug, Default)]
+pub struct BufWriter {
+    buf: Vec<u8>,
+}
+
+impl BufWriter {
+    pub fn new() -> BufWriter {
+        BufWriter { buf: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn to_buf(&self) -> Vec<u8> {
+        self.buf.clone()
+    }
+
+    pub fn into_buf(self) -> Vec<u8> {
+        self.buf
+    }
+
+    pub fn write(&mut self, buf: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(buf);
+        self
+    }
+
+    pub fn write_u8(&mut self, n: u8) -> &mut Self {
+        self.write(&[n])
+    }
+
+    pub fn write_u16_be(&mut self, n: u16) -> &mut Self {
+        self.write(&n.to_be_bytes())
+    }
+
+    pub fn write_u16_le(&mut self, n: u16) -> &mut Self {
+        self.write(&n.to_le_bytes())
+    }
+
+    pub fn write_u32_be(&mut self, n: u32) -> &mut Self {
+        self.write(&n.to_be_bytes())
+    }
+
+    pub fn write_u32_le(&mut self, n: u32) -> &mut Self {
+        self.write(&n.to_le_bytes())
+    }
+
+    pub fn write_u64_be(&mut self, n: u64) -> &mut Self {
+        self.write(&n.to_be_bytes())
+    }
+
+    pub fn write_u64_le(&mut self, n: u64) -> &mut Self {
+        self.write(&n.to_le_bytes())
+    }
+
+    pub fn write_u128_be(&mut self, n: u128) -> &mut Self {
+        self.write(&n.to_be_bytes())
+    }
+
+    pub fn write_u128_le(&mut self, n: u128) -> &mut Self {
+        self.write(&n.to_le_bytes())
+    }
+
+    /// Write a 256-bit integer, given as 32 big-endian bytes, in big-endian order
+    pub fn write_u256_be(&mut self, n: &[u8; 32]) -> &mut Self {
+        self.write(n)
+    }
+
+    /// Write a 256-bit integer, given as 32 big-endian bytes, in little-endian order
+    pub fn write_u256_le(&mut self, n: &[u8; 32]) -> &mut Self {
+        let mut buf = *n;
+        buf.reverse();
+        self.write(&buf)
+    }
+
+    pub fn write_var_int(&mut self, n: u64) -> &mut Self {
+        let buf = BufWriter::var_int_buf(n);
+        self.write(&buf)
+    }
+
+    /// Minimal VarInt encoding of `n`
+    pub fn var_int_buf(n: u64) -> Vec<u8> {
+        let mut writer = BufWriter::new();
+        if n < 0xfd {
+            writer.write_u8(n as u8);
+        } else if n < 0x10000 {
+            writer.write_u8(0xfd).write_u16_be(n as u16);
+        } else if n < 0x100000000 {
+            writer.write_u8(0xfe).write_u32_be(n as u32);
+        } else {
+            writer.write_u8(0xff).write_u64_be(n);
+        }
+        writer.into_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BufReader;
+    use hex::encode;
+
+    #[test]
+    fn test_write_integers() {
+        let mut writer = BufWriter::new();
+        assert!(writer.is_empty());
+        writer
+            .write_u8(0x01)
+            .write_u16_be(0x0203)
+            .write_u16_le(0x0405)
+            .write_u32_be(0x06070809)
+            .write_u32_le(0x0a0b0c0d);
+        assert_eq!(writer.len(), 13);
+        assert_eq!(encode(writer.to_buf()), "0102030504060708090d0c0b0a");
+
+        let mut writer = BufWriter::new();
+        writer
+            .write_u64_be(0x0102030405060708)
+            .write_u64_le(0x0102030405060708);
+        assert_eq!(
+            encode(writer.into_buf()),
+            "01020304050607080807060504030201"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let u256: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let mut writer = BufWriter::new();
+        writer
+            .write(b"webbuf")
+            .write_u8(u8::MAX)
+            .write_u16_be(u16::MAX)
+            .write_u32_le(u32::MAX)
+            .write_u64_be(u64::MAX)
+            .write_u128_le(u128::MAX - 1)
+            .write_u128_be(1)
+            .write_u256_be(&u256)
+            .write_u256_le(&u256)
+            .write_var_int(0xfc)
+            .write_var_int(0xfd)
+            .write_var_int(u64::MAX);
+        let buf = writer.into_buf();
+
+        let mut reader = BufReader::new(&buf);
+        assert_eq!(reader.read(6).unwrap(), b"webbuf");
+        assert_eq!(reader.read_u8().unwrap(), u8::MAX);
+        assert_eq!(reader.read_u16_be().unwrap(), u16::MAX);
+        assert_eq!(reader.read_u32_le().unwrap(), u32::MAX);
+        assert_eq!(reader.read_u64_be().unwrap(), u64::MAX);
+        assert_eq!(reader.read_u128_le().unwrap(), u128::MAX - 1);
+        assert_eq!(reader.read_u128_be().unwrap(), 1);
+        assert_eq!(reader.read_u256_be().unwrap(), u256);
+        assert_eq!(reader.read_u256_le().unwrap(), u256);
+        assert_eq!(reader.read_var_int().unwrap(), 0xfc);
+        assert_eq!(reader.read_var_int().unwrap(), 0xfd);
+        assert_eq!(reader.read_var_int().unwrap(), u64::MAX);
+        assert!(reader.eof());
+    }
+
+    #[test]
+    fn test_var_int_buf_sizes() {
+        assert_eq!(encode(BufWriter::var_int_buf(0)), "00");
+        assert_eq!(encode(BufWriter::var_int_buf(0xfc)), "fc");
+        assert_eq!(encode(BufWriter::var_int_buf(0xfd)), "fd00fd");
+        assert_eq!(encode(BufWriter::var_int_buf(0xffff)), "fdffff");
+        assert_eq!(encode(BufWriter::var_int_buf(0x10000)), "fe00010000");
+        assert_eq!(encode(BufWriter::var_int_buf(0xffffffff)), "feffffffff");
+        assert_eq!(
+            encode(BufWriter::var_int_buf(0x100000000)),
+            "ff0000000100000000"
+        );
+    }
+}
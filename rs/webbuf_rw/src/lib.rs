@@ -0,0 +1,5 @@
+mod buf_reader;
+mod buf_writer;
+
+pub use buf_reader::{BufReader, BufReaderError};
+pub use buf_writer::BufWriter;